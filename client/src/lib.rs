@@ -0,0 +1,135 @@
+//! Runs the `wordle` and `game_session` programs on a local gtest system.
+
+use game_session_io::*;
+use gstd::prelude::Decode;
+use gtest::{Program, ProgramBuilder, RunResult, System};
+use std::path::{Path, PathBuf};
+use wordle_io::WordleInit;
+
+pub mod play;
+
+pub const GAME_SESSION_PROGRAM_ID: u64 = 1;
+pub const WORDLE_PROGRAM_ID: u64 = 2;
+pub const USER: u64 = 3;
+
+/// Directory the workspace build puts the optimized wasm files in.
+pub fn default_wasm_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/wasm32-unknown-unknown/debug")
+}
+
+/// A local chain with both programs deployed and initialized.
+pub struct LocalGame {
+    system: System,
+}
+
+impl LocalGame {
+    pub fn new(
+        wasm_dir: &Path,
+        wordle_init: WordleInit,
+        config: GameSessionConfig,
+    ) -> Result<Self, String> {
+        for file in ["game_session.opt.wasm", "wordle.opt.wasm"] {
+            if !wasm_dir.join(file).exists() {
+                return Err(format!(
+                    "{} not found in {}, run `cargo build` first",
+                    file,
+                    wasm_dir.display()
+                ));
+            }
+        }
+
+        let system = System::new();
+        let game_session_program =
+            ProgramBuilder::from_file(wasm_dir.join("game_session.opt.wasm"))
+                .with_id(GAME_SESSION_PROGRAM_ID)
+                .build(&system);
+        let wordle_program = ProgramBuilder::from_file(wasm_dir.join("wordle.opt.wasm"))
+            .with_id(WORDLE_PROGRAM_ID)
+            .build(&system);
+
+        if wordle_program.send(USER, wordle_init).main_failed() {
+            return Err("Failed to initialize the wordle program".to_string());
+        }
+        let result = game_session_program.send(
+            USER,
+            GameSessionInit {
+                wordle_program_id: WORDLE_PROGRAM_ID.into(),
+                config,
+            },
+        );
+        if result.main_failed() {
+            return Err("Failed to initialize the game_session program".to_string());
+        }
+
+        Ok(Self { system })
+    }
+
+    pub fn system(&self) -> &System {
+        &self.system
+    }
+
+    fn game_session_program(&self) -> Program<'_> {
+        self.system
+            .get_program(GAME_SESSION_PROGRAM_ID)
+            .expect("The game_session program is deployed")
+    }
+
+    /// Sends `action` from `user` and decodes the program's reply.
+    pub fn send(&self, user: u64, action: GameSessionAction) -> Result<GameSessionEvent, String> {
+        self.send_measured(user, action).0
+    }
+
+    /// Like [`LocalGame::send`], also returning the gas burned by the whole message chain.
+    pub fn send_measured(
+        &self,
+        user: u64,
+        action: GameSessionAction,
+    ) -> (Result<GameSessionEvent, String>, u64) {
+        let result = self.game_session_program().send(user, action);
+        let gas_burned = result.main_gas_burned().0 + result.others_gas_burned().0;
+        (Self::reply(&result, user), gas_burned)
+    }
+
+    fn reply(result: &RunResult, user: u64) -> Result<GameSessionEvent, String> {
+        let mut events = Self::events_to(result, user);
+        if result.main_failed() {
+            let reason = result
+                .log()
+                .iter()
+                .find(|log| log.destination() == user.into())
+                .map(|log| String::from_utf8_lossy(log.payload()).into_owned())
+                .unwrap_or_else(|| "The message failed".to_string());
+            return Err(reason);
+        }
+        if events.is_empty() {
+            return Err("No reply from the game_session program".to_string());
+        }
+        Ok(events.remove(0))
+    }
+
+    /// Advances the chain and returns the messages the session sent to `user` meanwhile.
+    pub fn spend_blocks(&self, user: u64, blocks: u32) -> Vec<GameSessionEvent> {
+        self.system
+            .spend_blocks(blocks)
+            .iter()
+            .flat_map(|result| Self::events_to(result, user))
+            .collect()
+    }
+
+    pub fn state(&self) -> GameSessionState {
+        self.game_session_program()
+            .read_state(b"")
+            .expect("Failed to read the game_session state")
+    }
+
+    fn events_to(result: &RunResult, user: u64) -> Vec<GameSessionEvent> {
+        result
+            .log()
+            .iter()
+            .filter(|log| {
+                log.destination() == user.into() && log.source() == GAME_SESSION_PROGRAM_ID.into()
+            })
+            .filter_map(|log| GameSessionEvent::decode(&mut log.payload()).ok())
+            .collect()
+    }
+}
@@ -0,0 +1,145 @@
+use game_session_io::*;
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    process,
+};
+use wordle_client::{play::play_line, *};
+use wordle_core::Alphabet;
+use wordle_io::{BankInit, WordleInit, DEFAULT_BANK};
+
+const USAGE: &str = "\
+Usage: wordle-client [--script <file>] [--words <word-file>] [--alphabet <name>]
+                     [--wasm-dir <dir>]
+
+Type a five-letter word to guess it. Commands:
+    :new [bank] start a new game, from the bank `en` by default
+    :practice [bank]
+                start a practice game with unlimited tries and no timers
+    :reveal     give up the practice game and show its word
+    :giveup     give up the game, ranked or practice, and show its word
+    :hint       reveal a letter of the word for a try
+    :share      print the result grid of the finished game
+    :wait <n>   let <n> blocks pass
+    :state      print the session state
+    :quit       exit
+
+With `--script` the lines of <file> are played instead of reading stdin.
+`--words` replaces the words of the bank `en` with those of <word-file>,
+spelled in the `--alphabet`: en (default), es, de, de-translit or uk.";
+
+struct Args {
+    script: Option<PathBuf>,
+    words: Option<PathBuf>,
+    alphabet: Alphabet,
+    wasm_dir: PathBuf,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1);
+    let mut script = None;
+    let mut words = None;
+    let mut alphabet = Alphabet::English;
+    let mut wasm_dir = default_wasm_dir();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--script" => script = Some(args.next().ok_or("--script needs a file")?.into()),
+            "--words" => words = Some(args.next().ok_or("--words needs a file")?.into()),
+            "--alphabet" => {
+                let name = args.next().ok_or("--alphabet needs a name")?;
+                alphabet = Alphabet::from_name(&name)
+                    .ok_or_else(|| format!("Unknown alphabet `{}`", name))?
+            }
+            "--wasm-dir" => wasm_dir = args.next().ok_or("--wasm-dir needs a directory")?.into(),
+            "-h" | "--help" => return Err(String::new()),
+            _ => return Err(format!("Unexpected argument `{}`", arg)),
+        }
+    }
+    if words.is_none() && alphabet != Alphabet::English {
+        return Err("--alphabet needs --words".to_string());
+    }
+    Ok(Args {
+        script,
+        words,
+        alphabet,
+        wasm_dir,
+    })
+}
+
+/// The default bank, or a bank of the words of `args.words` if given.
+fn wordle_init(args: &Args) -> Result<WordleInit, String> {
+    let Some(path) = &args.words else {
+        return Ok(WordleInit::default());
+    };
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let words = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(str::split_whitespace)
+        .map(|word| match args.alphabet.parse(word) {
+            Ok(_) => Ok(word.to_string()),
+            Err(e) => Err(format!("`{}`: {}", word, e)),
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(WordleInit {
+        banks: vec![BankInit {
+            id: DEFAULT_BANK.to_string(),
+            alphabet: args.alphabet.into(),
+            words,
+            ..Default::default()
+        }],
+    })
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let game = LocalGame::new(
+        &args.wasm_dir,
+        wordle_init(&args)?,
+        GameSessionConfig::default(),
+    )?;
+    play_line(&game, ":new");
+
+    if let Some(script) = &args.script {
+        let script =
+            fs::read_to_string(script).map_err(|e| format!("{}: {}", script.display(), e))?;
+        for line in script
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+        {
+            println!("> {}", line);
+            if !play_line(&game, line) {
+                break;
+            }
+        }
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().map_err(|e| e.to_string())?;
+        let mut line = String::new();
+        if stdin
+            .lock()
+            .read_line(&mut line)
+            .map_err(|e| e.to_string())?
+            == 0
+        {
+            return Ok(());
+        }
+        if !play_line(&game, &line) {
+            return Ok(());
+        }
+    }
+}
+
+fn main() {
+    if let Err(error) = parse_args().and_then(run) {
+        if !error.is_empty() {
+            eprintln!("{}\n", error);
+        }
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
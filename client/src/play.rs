@@ -0,0 +1,174 @@
+//! The client's commands and how their replies are printed.
+
+use crate::{LocalGame, USER};
+use game_session_io::*;
+use wordle_io::DEFAULT_BANK;
+
+const GREEN: &str = "\x1b[30;42m";
+const YELLOW: &str = "\x1b[30;43m";
+const GRAY: &str = "\x1b[37;100m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders the guess as coloured tiles.
+fn tiles(word: &str, correct_positions: &[u8], contained_in_word: &[u8]) -> String {
+    word.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let colour = if correct_positions.contains(&(i as u8)) {
+                GREEN
+            } else if contained_in_word.contains(&(i as u8)) {
+                YELLOW
+            } else {
+                GRAY
+            };
+            format!("{} {} {}", colour, c.to_ascii_uppercase(), RESET)
+        })
+        .collect()
+}
+
+fn print_event(game: &LocalGame, last_guess: &str, event: &GameSessionEvent) {
+    match event {
+        GameSessionEvent::StartSuccess {
+            deadline_block: u32::MAX,
+            ..
+        } => println!("New practice game: unlimited tries, no time limit"),
+        GameSessionEvent::StartSuccess {
+            tries_left,
+            deadline_block,
+            ..
+        } => println!(
+            "New game: {} tries, ends at block {} (now {})",
+            tries_left,
+            deadline_block,
+            game.system().block_height()
+        ),
+        GameSessionEvent::CheckWordResult {
+            correct_positions,
+            contained_in_word,
+            deadline_block: u32::MAX,
+            ..
+        } => println!(
+            "{}",
+            tiles(last_guess, correct_positions, contained_in_word)
+        ),
+        GameSessionEvent::CheckWordResult {
+            correct_positions,
+            contained_in_word,
+            tries_left,
+            deadline_block,
+            ..
+        } => println!(
+            "{}  {} tries left, {} blocks left",
+            tiles(last_guess, correct_positions, contained_in_word),
+            tries_left,
+            deadline_block.saturating_sub(game.system().block_height())
+        ),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Win,
+            ..
+        } => {
+            println!("{}  You win!", tiles(last_guess, &[0, 1, 2, 3, 4], &[]))
+        }
+        GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word: Some(word),
+        } => {
+            println!(
+                "Game over, the word was {}. Type :new to play again",
+                word.to_uppercase()
+            )
+        }
+        GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word: None,
+        } => {
+            println!("Game over, you lose. Type :new to play again")
+        }
+        GameSessionEvent::InvalidWord => println!("Not a valid word, try again"),
+        GameSessionEvent::HardModeViolated { reason } => println!("{}, try again", reason),
+        GameSessionEvent::UnknownBank => println!("There is no such bank of words"),
+        GameSessionEvent::WordRevealed { word } => {
+            println!(
+                "The word was {}. Type :new to play again",
+                word.to_uppercase()
+            )
+        }
+        GameSessionEvent::NotPractice => println!("Only practice games can be revealed"),
+        GameSessionEvent::NoGame => println!("There is no game to give up"),
+        GameSessionEvent::HintRevealed {
+            position,
+            letter,
+            tries_left,
+            ..
+        } => println!(
+            "Letter {} is {}, {} tries left",
+            position + 1,
+            letter.to_uppercase(),
+            tries_left
+        ),
+        GameSessionEvent::HintUnavailable => println!("No hint available"),
+        GameSessionEvent::TooManySessions => {
+            println!("Too many games in progress, try again later")
+        }
+        event => println!("{:?}", event),
+    }
+}
+
+/// Handles one input line; returns `false` when the client should exit.
+pub fn play_line(game: &LocalGame, line: &str) -> bool {
+    let mut words = line.split_whitespace();
+    let reply = match (words.next(), words.next()) {
+        (None, _) => return true,
+        (Some(":quit"), _) => return false,
+        (Some(":state"), _) => {
+            println!("{:#?}", game.state());
+            return true;
+        }
+        (Some(":share"), _) => {
+            match game.state().share_grid(USER.into()) {
+                Some(grid) => println!("{}", grid),
+                None => println!("Finish a game to share it"),
+            }
+            return true;
+        }
+        (Some(":wait"), blocks) => {
+            let Some(blocks) = blocks.and_then(|n| n.parse().ok()) else {
+                println!("Usage: :wait <blocks>");
+                return true;
+            };
+            for event in game.spend_blocks(USER, blocks) {
+                print_event(game, "", &event);
+            }
+            return true;
+        }
+        (Some(":new"), bank) => game.send(
+            USER,
+            GameSessionAction::StartGame {
+                bank: bank.unwrap_or(DEFAULT_BANK).to_string(),
+                mode: GameMode::Ranked,
+            },
+        ),
+        (Some(":practice"), bank) => game.send(
+            USER,
+            GameSessionAction::StartGame {
+                bank: bank.unwrap_or(DEFAULT_BANK).to_string(),
+                mode: GameMode::Practice { tries_limit: None },
+            },
+        ),
+        (Some(":reveal"), _) => game.send(USER, GameSessionAction::Reveal),
+        (Some(":giveup"), _) => game.send(USER, GameSessionAction::GiveUp),
+        (Some(":hint"), _) => game.send(USER, GameSessionAction::RequestHint),
+        (Some(word), _) => game.send(
+            USER,
+            GameSessionAction::CheckWord {
+                word: word.to_lowercase(),
+                proof: Vec::new(),
+            },
+        ),
+    };
+    match reply {
+        Ok(event) => print_event(game, line.trim(), &event),
+        Err(error) => println!("Rejected: {}", error),
+    }
+    true
+}
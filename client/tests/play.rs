@@ -0,0 +1,37 @@
+use game_session_io::{GameSessionConfig, GameStatus, SessionStatus};
+use std::fs;
+use wordle_client::{default_wasm_dir, play::play_line, LocalGame, USER};
+use wordle_io::WordleInit;
+
+#[test]
+fn test_demo_script() {
+    let game = LocalGame::new(
+        &default_wasm_dir(),
+        WordleInit::default(),
+        GameSessionConfig::default(),
+    )
+    .unwrap();
+    assert!(play_line(&game, ":new"));
+
+    // The demo guesses every word of the default bank, so one of them wins
+    let script = fs::read_to_string("scripts/demo.txt").unwrap();
+    for line in script
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+    {
+        assert!(play_line(&game, line));
+    }
+    let state = game.state();
+    let (_, session_info) = state
+        .game_sessions
+        .iter()
+        .find(|(user, _)| *user == USER.into())
+        .unwrap();
+    assert_eq!(
+        session_info.session_status,
+        SessionStatus::GameOver(GameStatus::Win)
+    );
+    assert!(state.share_grid(USER.into()).is_some());
+
+    assert!(!play_line(&game, ":quit"));
+}
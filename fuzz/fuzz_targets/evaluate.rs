@@ -0,0 +1,35 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wordle_core::{evaluate, Feedback, Word, WORD_LENGTH};
+
+fn count(word: &Word, letter: char) -> usize {
+    word.iter().filter(|&&c| c == letter).count()
+}
+
+fuzz_target!(|words: (Word, Word)| {
+    let (secret, guess) = words;
+    let feedback = evaluate(&secret, &guess);
+    let correct: Vec<u8> = feedback.correct_positions().collect();
+    let contained: Vec<u8> = feedback.contained_in_word().collect();
+
+    // Indices are unique, in range and never both correct and contained
+    for positions in [&correct, &contained] {
+        assert!(positions.iter().all(|&i| (i as usize) < WORD_LENGTH));
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+    assert!(correct.iter().all(|i| !contained.contains(i)));
+
+    // A letter is never marked more often than the secret contains it
+    for letter in guess {
+        let marked = correct
+            .iter()
+            .chain(&contained)
+            .filter(|&&i| guess[i as usize] == letter)
+            .count();
+        assert!(marked <= count(&secret, letter));
+    }
+
+    assert_eq!(Feedback::from_positions(&correct, &contained), feedback);
+    assert_eq!(feedback.is_solved(), secret == guess);
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use game_session_io::GameSessionAction;
+use gstd::prelude::Encode;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|payload: &[u8]| {
+    if let Some(action) = GameSessionAction::from_payload(payload) {
+        assert_eq!(action.encode(), payload);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use gstd::prelude::Encode;
+use libfuzzer_sys::fuzz_target;
+use wordle_io::Action;
+
+fuzz_target!(|payload: &[u8]| {
+    if let Some(action) = Action::from_payload(payload) {
+        assert_eq!(action.encode(), payload);
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use game_session_io::WordleEvent;
+use gstd::prelude::Encode;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|payload: &[u8]| {
+    if let Some(event) = WordleEvent::from_payload(payload) {
+        assert_eq!(event.encode(), payload);
+        // Anything the session accepts from Wordle must be safe to score
+        event.has_guessed();
+    }
+});
@@ -1,7 +1,10 @@
 #![no_std]
 
+use core::cmp::Ordering;
 use gmeta::{In, InOut, Metadata, Out};
-use gstd::{collections::HashMap, prelude::*, ActorId, MessageId, PartialEq};
+use gstd::{collections::HashMap, exec, prelude::*, ActorId, MessageId, PartialEq, ReservationId};
+use parity_scale_codec::DecodeAll;
+use wordle_core::{Feedback, LetterState, WORD_LENGTH};
 
 pub struct GameSessionMetadata;
 
@@ -10,19 +13,91 @@ impl Metadata for GameSessionMetadata {
     type Handle = InOut<GameSessionAction, GameSessionEvent>;
     type State = Out<GameSessionState>;
     type Reply = ();
-    type Others = ();
+    type Others = Out<LifecycleEvent>;
     type Signal = ();
 }
 
 #[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
 pub struct GameSessionState {
     pub wordle_program_id: ActorId,
+    pub owner: ActorId,
+    pub config: GameSessionConfig,
+    pub valid_reservations: u32,
+    /// Hint fees collected and not withdrawn yet.
+    pub fees: u128,
     pub game_sessions: Vec<(ActorId, SessionInfo)>,
+    /// Results of archived games, the oldest first.
+    pub results: Vec<GameResult>,
+    /// Groups by id.
+    pub groups: Vec<Group>,
+    /// Stats of every player with a finished ranked game, which the group leaderboards rank.
+    pub stats: Vec<(ActorId, PlayerStats)>,
+}
+
+/// Blocks in a day at six-second blocks; daily results count days from block 0.
+///
+/// These block-days only approximate calendar days, and as every player has secrets of their
+/// own, a day is no shared puzzle either.
+pub const BLOCKS_PER_DAY: u32 = 14_400;
+
+// Queries for frontends over the whole state as `read_state` returns it
+impl GameSessionState {
+    /// `SessionInfo::share_grid` of the user's last game.
+    pub fn share_grid(&self, user: ActorId) -> Option<String> {
+        self.game_sessions
+            .iter()
+            .find(|(other, _)| *other == user)
+            .and_then(|(_, session_info)| session_info.share_grid())
+    }
+
+    /// Stats of the group's members who played ranked games, the best first by
+    /// `PlayerStats::rank`.
+    pub fn group_leaderboard(&self, group_id: u32) -> Vec<(ActorId, PlayerStats)> {
+        let Some(group) = self.groups.get(group_id as usize) else {
+            return Vec::new();
+        };
+        let mut leaderboard: Vec<_> = self
+            .stats
+            .iter()
+            .filter(|(user, _)| group.members.contains(user))
+            .cloned()
+            .collect();
+        leaderboard.sort_by(|(_, stats), (_, other)| stats.rank(other));
+        leaderboard
+    }
+
+    /// Ranked games of the group's members that ended on the block-day `day`, the earliest first.
+    ///
+    /// Results pruned beyond `max_results` are missing.
+    pub fn group_daily_results(&self, group_id: u32, day: u32) -> Vec<GameResult> {
+        let Some(group) = self.groups.get(group_id as usize) else {
+            return Vec::new();
+        };
+        let on_day = |result: &GameResult| {
+            group.members.contains(&result.user) && result.ended_block / BLOCKS_PER_DAY == day
+        };
+        // Finished sessions that are not archived yet count too
+        let finished = self
+            .game_sessions
+            .iter()
+            .filter(|(_, session_info)| !session_info.is_practice())
+            .filter_map(|(user, session_info)| session_info.result(*user));
+        let mut results: Vec<_> = self
+            .results
+            .iter()
+            .cloned()
+            .chain(finished)
+            .filter(on_day)
+            .collect();
+        results.sort_by_key(|result| result.ended_block);
+        results
+    }
 }
 
 #[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
 pub struct GameSessionInit {
     pub wordle_program_id: ActorId,
+    pub config: GameSessionConfig,
 }
 
 impl GameSessionInit {
@@ -31,6 +106,7 @@ impl GameSessionInit {
             !self.wordle_program_id.is_zero(),
             "Invalid wordle_program_id"
         );
+        self.config.assert_valid();
     }
 }
 
@@ -38,37 +114,368 @@ impl From<GameSessionInit> for GameSession {
     fn from(game_session_init: GameSessionInit) -> Self {
         Self {
             wordle_program_id: game_session_init.wordle_program_id,
+            config: game_session_init.config,
             ..Default::default()
         }
     }
 }
 
+/// Game limits and timers, gas reservations for the delayed messages, storage limits and the
+/// price of hints.
+#[derive(Debug, Clone, Copy, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct GameSessionConfig {
+    /// Maximum number of tries allowed in the game.
+    pub tries_limit: u8,
+    /// Whether games start in hard mode, where every guess must use the hints revealed by the
+    /// previous one.
+    pub hard_mode: bool,
+    /// Number of blocks before the game is checked for a timeout.
+    pub game_timeout: u32,
+    /// Gas reserved for every delayed timeout message.
+    pub reservation_amount: u64,
+    /// Number of blocks a reservation stays valid.
+    pub reservation_duration: u32,
+    /// Blocks the player has for each guess; `None` disables the move timer.
+    pub move_timeout: Option<u32>,
+    /// What to do with a request that arrives while the session is busy.
+    pub busy_policy: BusyPolicy,
+    /// Blocks after which a request's lock on the session expires, freeing the sessions of
+    /// requests that failed while waiting for Wordle; it must outlast the replies they wait for.
+    pub lock_timeout: u32,
+    /// Games in progress at once; `StartGame` is refused with `TooManySessions` beyond it.
+    pub max_active_sessions: Option<u32>,
+    /// Blocks a finished session is kept before a cleanup archives it into a `GameResult`.
+    pub archive_after: u32,
+    /// Archived results kept; a cleanup prunes the oldest beyond it.
+    pub max_results: u32,
+    /// Blocks between cleanups the program schedules itself, paid from pooled reservations;
+    /// `None` leaves them to the owner.
+    pub cleanup_interval: Option<u32>,
+    /// What a `RequestHint` costs the player.
+    pub hint_cost: HintCost,
+}
+
+/// Handling of a player's request while their previous one still waits for Wordle.
+#[derive(Debug, Default, Clone, Copy, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub enum BusyPolicy {
+    /// Reply with `GameSessionEvent::SessionBusy` right away.
+    #[default]
+    Reject,
+    /// Wait for the session to be released, keeping up to `limit` requests queued.
+    Queue { limit: u8 },
+}
+
+/// The price of a hint.
+#[derive(Debug, Default, Clone, Copy, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub enum HintCost {
+    /// A try of the game; the last try cannot be spent on a hint.
+    #[default]
+    Try,
+    /// Value attached to the `RequestHint` message; any excess is returned with the reply.
+    Fee(u128),
+}
+
+impl Default for GameSessionConfig {
+    fn default() -> Self {
+        Self {
+            tries_limit: 5,
+            hard_mode: false,
+            game_timeout: 200,
+            reservation_amount: 10_000_000_000,
+            reservation_duration: 86_400,
+            move_timeout: None,
+            busy_policy: BusyPolicy::Reject,
+            lock_timeout: 1_000,
+            max_active_sessions: None,
+            archive_after: 14_400,
+            max_results: 1_000,
+            cleanup_interval: None,
+            hint_cost: HintCost::Try,
+        }
+    }
+}
+
+impl GameSessionConfig {
+    pub fn assert_valid(&self) {
+        assert!(self.tries_limit > 0, "Invalid tries_limit");
+        assert!(self.game_timeout > 0, "Invalid game_timeout");
+        assert!(self.reservation_amount > 0, "Invalid reservation_amount");
+        assert!(
+            self.reservation_duration > 0,
+            "Invalid reservation_duration"
+        );
+        assert!(self.move_timeout != Some(0), "Invalid move_timeout");
+        assert!(self.lock_timeout > 0, "Invalid lock_timeout");
+        assert!(self.max_results > 0, "Invalid max_results");
+        assert!(self.cleanup_interval != Some(0), "Invalid cleanup_interval");
+    }
+}
+
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
 pub enum GameSessionAction {
-    StartGame,
+    /// Start a game with a secret from the Wordle bank `bank`, e.g. `en` or `themed:space`.
+    StartGame {
+        bank: String,
+        mode: GameMode,
+    },
+    /// `proof` is the Merkle proof Wordle needs for guesses from a committed dictionary;
+    /// leave it empty otherwise.
     CheckWord {
         word: String,
+        proof: Vec<[u8; 32]>,
     },
     CheckGameStatus {
         user: ActorId,
         session_id: MessageId,
     },
+    /// Sent by the program itself when a move timer expires.
+    CheckMoveStatus {
+        user: ActorId,
+        session_id: MessageId,
+        move_number: u8,
+    },
+    /// Owner only: reserve `count` more gas reservations from the attached gas.
+    TopUpReservations {
+        count: u32,
+    },
+    /// Owner only: replace the whole config, from the game limits and timers to the gas
+    /// reservations, the busy policy, the storage limits and the hint cost.
+    UpdateConfig(GameSessionConfig),
+    /// Owner only, or sent by the program itself every `cleanup_interval` blocks: archive
+    /// finished sessions and prune old results.
+    Cleanup,
+    /// Give up a practice game and learn its word, replied with `WordRevealed`, or with
+    /// `GameOver` without the word if Wordle does not answer.
+    Reveal,
+    /// End the current game, ranked or practice, as a loss and learn its word.
+    GiveUp,
+    /// Learn the letter at a position not found yet, for the config's `hint_cost`.
+    ///
+    /// Hinted wins do not count as pure wins.
+    RequestHint,
+    /// Create a group with the sender as its owner and first member.
+    CreateGroup {
+        name: String,
+    },
+    /// Members only: invite `user` to join the group.
+    InviteToGroup {
+        group_id: u32,
+        user: ActorId,
+    },
+    /// Join a group the sender was invited to.
+    JoinGroup {
+        group_id: u32,
+    },
+    LeaveGroup {
+        group_id: u32,
+    },
+    /// Owner only: send the hint fees collected so far to the owner.
+    WithdrawFees,
+}
+
+/// How a game is played and whether it counts.
+#[derive(Debug, Default, Clone, Copy, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub enum GameMode {
+    /// `tries_limit` tries and the timers of the config; the result counts toward stats.
+    #[default]
+    Ranked,
+    /// `tries_limit` tries, unlimited with `None`, and no timers; the word can be revealed.
+    ///
+    /// Practice games emit no `LifecycleEvent`s and are not archived, so they never count
+    /// toward stats or leaderboards.
+    Practice { tries_limit: Option<u8> },
+}
+
+impl GameMode {
+    /// Tries of a game in this mode given the config's `tries_limit`, or `None` if unlimited.
+    pub fn tries_limit(&self, config_tries_limit: u8) -> Option<u8> {
+        match self {
+            GameMode::Ranked => Some(config_tries_limit),
+            GameMode::Practice { tries_limit } => *tries_limit,
+        }
+    }
+}
+
+impl GameSessionAction {
+    /// Decodes a whole message payload; malformed payloads and trailing bytes are rejected.
+    pub fn from_payload(mut payload: &[u8]) -> Option<Self> {
+        Self::decode_all(&mut payload).ok()
+    }
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
 pub enum WordleAction {
-    StartGame { user: ActorId },
-    CheckWord { user: ActorId, word: String },
+    StartGame {
+        user: ActorId,
+        bank: String,
+        hard_mode: bool,
+    },
+    CheckWord {
+        user: ActorId,
+        word: String,
+        proof: Vec<[u8; 32]>,
+    },
+    // Same index as in `wordle_io::Action`, which has the owner's actions before it
+    #[codec(index = 4)]
+    RevealWord { user: ActorId },
+    #[codec(index = 5)]
+    RevealHint { user: ActorId, known: Vec<u8> },
 }
 
-#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq)]
 pub enum GameSessionEvent {
-    StartSuccess,
+    StartSuccess {
+        game_id: MessageId,
+        tries_left: u8,
+        deadline_block: u32,
+    },
     CheckWordResult {
+        game_id: MessageId,
         correct_positions: Vec<u8>,
         contained_in_word: Vec<u8>,
+        tries_left: u8,
+        deadline_block: u32,
+    },
+    /// `word` is the secret word of a lost game, as revealed by Wordle. It is `None` for wins,
+    /// when Wordle did not answer and when a timer already sent the word.
+    GameOver {
+        status: GameStatus,
+        word: Option<String>,
+    },
+    /// The Wordle program failed to handle the request; the session is rolled back.
+    RequestFailed,
+    /// Another request of the player is in progress and this one was not queued.
+    SessionBusy,
+    ReservationsToppedUp {
+        valid_reservations: u32,
+    },
+    ConfigUpdated(GameSessionConfig),
+    /// The message payload is not a valid `GameSessionAction`.
+    InvalidPayload,
+    /// The guess is not a word of the bank's alphabet or dictionary; no try is used.
+    InvalidWord,
+    /// The hard mode guess drops a hint of the previous guess, as `reason` tells; no try is used.
+    HardModeViolated {
+        reason: String,
+    },
+    /// Wordle has no bank with the requested id, or it has no words yet; no game was started.
+    UnknownBank,
+    /// `max_active_sessions` games are in progress; no game was started.
+    TooManySessions,
+    CleanedUp {
+        archived: u32,
+        pruned: u32,
+    },
+    /// The practice game is over and its word was `word`.
+    WordRevealed {
+        word: String,
+    },
+    /// Only practice games waiting for a guess can be revealed.
+    NotPractice,
+    /// The player has no game waiting for a guess, so there is nothing to give up.
+    NoGame,
+    /// `letter` is at `position` of the word; `tries_left` accounts for the hint's cost.
+    HintRevealed {
+        game_id: MessageId,
+        position: u8,
+        letter: String,
+        tries_left: u8,
+    },
+    /// No hint was given and the attached value is returned: there is no game waiting for a
+    /// guess, every position is known, or the player cannot pay the `hint_cost`.
+    HintUnavailable,
+    GroupCreated {
+        group_id: u32,
+    },
+    InvitedToGroup {
+        group_id: u32,
+        user: ActorId,
+    },
+    JoinedGroup {
+        group_id: u32,
+    },
+    LeftGroup {
+        group_id: u32,
+    },
+    /// The reply carries the withdrawn hint fees, `amount` in total.
+    FeesWithdrawn {
+        amount: u128,
+    },
+}
+
+/// Events the program emits to `ActorId::zero()` at every step of a game, for indexers and
+/// frontends.
+///
+/// The schema is stable: variants keep their codec indices and new ones are only appended.
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq)]
+pub enum LifecycleEvent {
+    #[codec(index = 0)]
+    GameStarted {
+        user: ActorId,
+        game_id: MessageId,
+        bank: String,
+        deadline_block: u32,
+    },
+    /// The guess was sent to Wordle; a `GuessEvaluated` follows unless Wordle rejects it.
+    #[codec(index = 1)]
+    GuessSubmitted {
+        user: ActorId,
+        game_id: MessageId,
+        word: String,
+    },
+    /// `tries` counts this guess.
+    #[codec(index = 2)]
+    GuessEvaluated {
+        user: ActorId,
+        game_id: MessageId,
+        tries: u8,
+        correct_positions: Vec<u8>,
+        contained_in_word: Vec<u8>,
+    },
+    #[codec(index = 3)]
+    GameWon {
+        user: ActorId,
+        game_id: MessageId,
+        tries: u8,
+    },
+    /// The player used up all tries.
+    #[codec(index = 4)]
+    GameLost {
+        user: ActorId,
+        game_id: MessageId,
+        tries: u8,
+    },
+    /// A timer ended the game with a loss; `move_number` is set for the move timer.
+    #[codec(index = 5)]
+    TimedOut {
+        user: ActorId,
+        game_id: MessageId,
+        move_number: Option<u8>,
+    },
+    #[codec(index = 6)]
+    ConfigChanged(GameSessionConfig),
+    /// The player ended the game with `GiveUp` after `tries` guesses.
+    #[codec(index = 7)]
+    GaveUp {
+        user: ActorId,
+        game_id: MessageId,
+        tries: u8,
+    },
+    /// `tries` counts the hint if it cost a try.
+    #[codec(index = 8)]
+    HintRevealed {
+        user: ActorId,
+        game_id: MessageId,
+        position: u8,
+        tries: u8,
     },
-    GameOver(GameStatus),
+}
+
+impl LifecycleEvent {
+    /// Decodes a whole message payload; malformed payloads and trailing bytes are rejected.
+    pub fn from_payload(mut payload: &[u8]) -> Option<Self> {
+        Self::decode_all(&mut payload).ok()
+    }
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq)]
@@ -87,42 +494,79 @@ pub enum WordleEvent {
         correct_positions: Vec<u8>,
         contained_in_word: Vec<u8>,
     },
+    // Same index as in `wordle_io::Event`, which has `InvalidPayload` before it
+    #[codec(index = 3)]
+    InvalidWord {
+        user: ActorId,
+    },
+    #[codec(index = 4)]
+    UnknownBank {
+        user: ActorId,
+    },
+    #[codec(index = 7)]
+    WordRevealed {
+        user: ActorId,
+        word: String,
+    },
+    #[codec(index = 8)]
+    HintRevealed {
+        user: ActorId,
+        position: u8,
+        letter: String,
+    },
+    #[codec(index = 9)]
+    HardModeViolated {
+        user: ActorId,
+        reason: String,
+    },
 }
 
 impl WordleEvent {
+    /// Decodes a whole reply payload; malformed payloads and trailing bytes are rejected.
+    pub fn from_payload(mut payload: &[u8]) -> Option<Self> {
+        Self::decode_all(&mut payload).ok()
+    }
+
     pub fn get_user(&self) -> &ActorId {
         match self {
             WordleEvent::GameStarted { user } => user,
             WordleEvent::WordChecked { user, .. } => user,
+            WordleEvent::InvalidWord { user } => user,
+            WordleEvent::UnknownBank { user } => user,
+            WordleEvent::WordRevealed { user, .. } => user,
+            WordleEvent::HintRevealed { user, .. } => user,
+            WordleEvent::HardModeViolated { user, .. } => user,
         }
     }
 
     pub fn has_guessed(&self) -> bool {
         match self {
-            WordleEvent::GameStarted { .. } => unimplemented!(),
-            WordleEvent::WordChecked {
-                correct_positions, ..
-            } => correct_positions == &vec![0, 1, 2, 3, 4],
-        }
-    }
-}
-
-impl From<&WordleEvent> for GameSessionEvent {
-    fn from(wordle_event: &WordleEvent) -> Self {
-        match wordle_event {
-            WordleEvent::GameStarted { .. } => GameSessionEvent::StartSuccess,
+            WordleEvent::GameStarted { .. }
+            | WordleEvent::InvalidWord { .. }
+            | WordleEvent::UnknownBank { .. }
+            | WordleEvent::WordRevealed { .. }
+            | WordleEvent::HintRevealed { .. }
+            | WordleEvent::HardModeViolated { .. } => false,
             WordleEvent::WordChecked {
                 correct_positions,
                 contained_in_word,
                 ..
-            } => GameSessionEvent::CheckWordResult {
-                correct_positions: correct_positions.clone(),
-                contained_in_word: contained_in_word.clone(),
-            },
+            } => Feedback::from_positions(correct_positions, contained_in_word).is_solved(),
         }
     }
 }
 
+/// State of a player's session.
+///
+/// Transition table (`-` marks an illegal transition):
+///
+/// | from \ on                 | `Start`                | `Started`       | `Submit`                   | `Checked`       | `Finish(status)`   | `Cancel`        |
+/// |----------------------------|------------------------|-----------------|----------------------------|-----------------|--------------------|-----------------|
+/// | `Init`                     | `WaitWordleStartReply` | -               | -                          | -               | -                  | -               |
+/// | `WaitWordleStartReply`     | -                      | `WaitUserInput` | -                          | -               | -                  | `Init`          |
+/// | `WaitUserInput`            | -                      | -               | `WaitWordleCheckWordReply` | -               | `GameOver(status)` | -               |
+/// | `WaitWordleCheckWordReply` | -                      | -               | -                          | `WaitUserInput` | `GameOver(status)` | `WaitUserInput` |
+/// | `GameOver(_)`              | `WaitWordleStartReply` | -               | -                          | -               | -                  | -               |
 #[derive(Default, Debug, Clone, Encode, Decode, TypeInfo, PartialEq)]
 pub enum SessionStatus {
     #[default]
@@ -130,20 +574,262 @@ pub enum SessionStatus {
     WaitUserInput,
     WaitWordleStartReply,
     WaitWordleCheckWordReply,
-    ReplyReceived(WordleEvent),
     GameOver(GameStatus),
 }
 
+/// Inputs driving the [`SessionStatus`] state machine.
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq)]
+pub enum Transition {
+    /// The player sent `StartGame`.
+    Start,
+    /// Wordle confirmed the game start.
+    Started,
+    /// The player sent `CheckWord`.
+    Submit,
+    /// Wordle checked a guess that did not end the game.
+    Checked,
+    /// The game ended by a guess, exhausted tries or a timer.
+    Finish(GameStatus),
+    /// The request to Wordle failed.
+    Cancel,
+}
+
+impl SessionStatus {
+    /// Returns the status after `transition`, or `None` if it is illegal.
+    pub fn next(&self, transition: &Transition) -> Option<SessionStatus> {
+        use SessionStatus::*;
+
+        match (self, transition) {
+            (Init | GameOver(..), Transition::Start) => Some(WaitWordleStartReply),
+            (WaitWordleStartReply, Transition::Started) => Some(WaitUserInput),
+            (WaitWordleStartReply, Transition::Cancel) => Some(Init),
+            (WaitUserInput, Transition::Submit) => Some(WaitWordleCheckWordReply),
+            (WaitWordleCheckWordReply, Transition::Checked | Transition::Cancel) => {
+                Some(WaitUserInput)
+            }
+            (WaitUserInput | WaitWordleCheckWordReply, Transition::Finish(status)) => {
+                Some(GameOver(status.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, Encode, Decode, TypeInfo)]
 pub struct SessionInfo {
     pub session_id: MessageId,
-    pub original_msg_id: MessageId,
-    pub send_to_wordle_msg_id: MessageId,
+    /// Wordle bank of the current or last game.
+    pub bank: String,
+    pub mode: GameMode,
     pub tries: u8,
+    /// Tries of the current or last game, fixed when it starts; `None` if unlimited.
+    pub tries_limit: Option<u8>,
+    /// `u32::MAX` in practice games, which have no timers.
+    pub deadline_block: u32,
     pub session_status: SessionStatus,
+    /// Block the last game ended at.
+    pub ended_block: u32,
+    /// Message currently holding the session while it waits for Wordle.
+    pub lock: Option<MessageId>,
+    /// Block the lock was taken at.
+    pub locked_at: u32,
+    /// Messages waiting for the lock, in arrival order.
+    pub queue: Vec<MessageId>,
+    /// Positions of the word found by guesses or hints in the current or last game.
+    pub found_positions: Vec<u8>,
+    /// Letters hinted in the current or last game, in order.
+    pub hints: Vec<Hint>,
+    /// Colours of the guesses of the current or last game, in order.
+    pub guesses: Vec<GuessColours>,
+}
+
+/// The feedback of a guess without its letters.
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct GuessColours {
+    pub correct_positions: Vec<u8>,
+    pub contained_in_word: Vec<u8>,
+}
+
+/// A letter of the secret word revealed by `RequestHint`.
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct Hint {
+    pub position: u8,
+    pub letter: String,
 }
 
 impl SessionInfo {
+    /// Moves the session along the state machine, panicking on an illegal transition.
+    pub fn apply(&mut self, transition: Transition) {
+        self.session_status = self
+            .session_status
+            .next(&transition)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Illegal transition {:?} from {:?}",
+                    transition, self.session_status
+                )
+            });
+    }
+
+    /// Tries left, `u8::MAX` if unlimited.
+    pub fn tries_left(&self) -> u8 {
+        self.tries_limit.map_or(u8::MAX, |tries_limit| {
+            tries_limit.saturating_sub(self.tries)
+        })
+    }
+
+    /// Whether the game has used all its tries.
+    pub fn is_out_of_tries(&self) -> bool {
+        self.tries_limit
+            .is_some_and(|tries_limit| self.tries >= tries_limit)
+    }
+
+    pub fn is_practice(&self) -> bool {
+        matches!(self.mode, GameMode::Practice { .. })
+    }
+
+    /// Records the positions a guess or a hint found.
+    pub fn add_found_positions(&mut self, positions: &[u8]) {
+        for position in positions {
+            if !self.found_positions.contains(position) {
+                self.found_positions.push(*position);
+            }
+        }
+    }
+
+    /// The result of the last game once it is over.
+    pub fn result(&self, user: ActorId) -> Option<GameResult> {
+        let SessionStatus::GameOver(status) = &self.session_status else {
+            return None;
+        };
+        Some(GameResult {
+            user,
+            game_id: self.session_id,
+            bank: self.bank.clone(),
+            status: status.clone(),
+            tries: self.tries,
+            ended_block: self.ended_block,
+            hints: self.hints.clone(),
+        })
+    }
+
+    /// The result of the last game to share once it is over, e.g.
+    ///
+    /// ```text
+    /// Wordle day 42 3/5
+    ///
+    /// ⬛🟨⬛⬛⬛
+    /// ⬛🟩🟨⬛🟩
+    /// 🟩🟩🟩🟩🟩
+    /// ```
+    ///
+    /// The game is dated by the block-day it ended on, as daily results are. A lost game
+    /// scores `X`, unlimited practice games are out of `∞` and hints are counted after the
+    /// score. Letters are never shown. `None` while no game is over.
+    pub fn share_grid(&self) -> Option<String> {
+        let SessionStatus::GameOver(status) = &self.session_status else {
+            return None;
+        };
+        let score = match status {
+            GameStatus::Win => self.tries.to_string(),
+            GameStatus::Lose => "X".to_string(),
+        };
+        let limit = self
+            .tries_limit
+            .map_or("∞".to_string(), |limit| limit.to_string());
+        let mut grid = format!(
+            "Wordle day {} {}/{}",
+            self.ended_block / BLOCKS_PER_DAY,
+            score,
+            limit
+        );
+        match self.hints.len() {
+            0 => {}
+            1 => grid.push_str(" (1 hint)"),
+            hints => grid.push_str(&format!(" ({} hints)", hints)),
+        }
+        grid.push('\n');
+        for guess in &self.guesses {
+            grid.push('\n');
+            let feedback =
+                Feedback::from_positions(&guess.correct_positions, &guess.contained_in_word);
+            grid.extend(feedback.0.iter().map(|state| match state {
+                LetterState::Correct => '🟩',
+                LetterState::Present => '🟨',
+                LetterState::Absent => '⬛',
+            }));
+        }
+        Some(grid)
+    }
+
+    /// Whether a hint can be given: the game waits for a guess and some position is unknown.
+    pub fn can_hint(&self) -> bool {
+        self.session_status == SessionStatus::WaitUserInput
+            && self.found_positions.len() < WORD_LENGTH
+    }
+
+    /// Builds the player-facing reply for a Wordle event in this session.
+    pub fn reply_event(&self, wordle_event: &WordleEvent) -> GameSessionEvent {
+        match wordle_event {
+            WordleEvent::GameStarted { .. } => GameSessionEvent::StartSuccess {
+                game_id: self.session_id,
+                tries_left: self.tries_left(),
+                deadline_block: self.deadline_block,
+            },
+            WordleEvent::WordChecked {
+                correct_positions,
+                contained_in_word,
+                ..
+            } => GameSessionEvent::CheckWordResult {
+                game_id: self.session_id,
+                correct_positions: correct_positions.clone(),
+                contained_in_word: contained_in_word.clone(),
+                tries_left: self.tries_left(),
+                deadline_block: self.deadline_block,
+            },
+            WordleEvent::InvalidWord { .. } => GameSessionEvent::InvalidWord,
+            WordleEvent::HardModeViolated { reason, .. } => GameSessionEvent::HardModeViolated {
+                reason: reason.clone(),
+            },
+            WordleEvent::UnknownBank { .. } => GameSessionEvent::UnknownBank,
+            WordleEvent::WordRevealed { word, .. } => {
+                GameSessionEvent::WordRevealed { word: word.clone() }
+            }
+            WordleEvent::HintRevealed {
+                position, letter, ..
+            } => GameSessionEvent::HintRevealed {
+                game_id: self.session_id,
+                position: *position,
+                letter: letter.clone(),
+                tries_left: self.tries_left(),
+            },
+        }
+    }
+
+    /// Whether a request holds the session at `block_height`. A lock older than `lock_timeout`
+    /// blocks belongs to a request that failed while waiting for Wordle and no longer counts.
+    pub fn is_locked(&self, block_height: u32, lock_timeout: u32) -> bool {
+        self.lock.is_some() && self.locked_at.saturating_add(lock_timeout) > block_height
+    }
+
+    /// Gives the session to the message `msg_id` at `block_height`, cancelling the wait for
+    /// Wordle of a request whose lock expired.
+    pub fn take_lock(&mut self, msg_id: MessageId, block_height: u32) {
+        if self.lock.is_some() && self.is_wait_reply_status() {
+            self.apply(Transition::Cancel);
+        }
+        self.lock = Some(msg_id);
+        self.locked_at = block_height;
+    }
+
+    /// Whether a game is in progress, the start included.
+    pub fn is_active(&self) -> bool {
+        !matches!(
+            self.session_status,
+            SessionStatus::Init | SessionStatus::GameOver(..)
+        )
+    }
+
     pub fn is_wait_reply_status(&self) -> bool {
         matches!(
             self.session_status,
@@ -152,21 +838,184 @@ impl SessionInfo {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct GasReservation {
+    pub id: ReservationId,
+    pub valid_until: u32,
+}
+
+/// Compact record of a finished game, kept after its session is dropped.
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq)]
+pub struct GameResult {
+    pub user: ActorId,
+    pub game_id: MessageId,
+    pub bank: String,
+    pub status: GameStatus,
+    pub tries: u8,
+    pub ended_block: u32,
+    pub hints: Vec<Hint>,
+}
+
+impl GameResult {
+    /// A win without hints.
+    pub fn is_pure_win(&self) -> bool {
+        self.status == GameStatus::Win && self.hints.is_empty()
+    }
+}
+
+/// Totals of a player's ranked games.
+#[derive(Debug, Default, Clone, Copy, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct PlayerStats {
+    pub games: u32,
+    pub wins: u32,
+    /// Wins without hints.
+    pub pure_wins: u32,
+    /// Tries of all wins, for the average.
+    pub win_tries: u32,
+}
+
+impl PlayerStats {
+    pub fn record(&mut self, result: &GameResult) {
+        self.games += 1;
+        if result.status == GameStatus::Win {
+            self.wins += 1;
+            self.win_tries += result.tries as u32;
+            if result.is_pure_win() {
+                self.pure_wins += 1;
+            }
+        }
+    }
+
+    /// Orders the better player first: more pure wins, then more wins, then fewer average tries.
+    pub fn rank(&self, other: &Self) -> Ordering {
+        other
+            .pure_wins
+            .cmp(&self.pure_wins)
+            .then(other.wins.cmp(&self.wins))
+            // Cross-multiplied to compare the averages without division
+            .then(
+                (self.win_tries as u64 * other.wins as u64)
+                    .cmp(&(other.win_tries as u64 * self.wins as u64)),
+            )
+    }
+}
+
+/// Players sharing leaderboards and daily results.
+///
+/// When the owner leaves, the longest-standing member takes over. A group whose last member
+/// left is deleted: it is reset to the default, and its id goes to the next group created.
+#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct Group {
+    pub name: String,
+    pub owner: ActorId,
+    pub members: Vec<ActorId>,
+    /// Players invited and not joined yet.
+    pub invited: Vec<ActorId>,
+}
+
+impl Group {
+    /// Longest group name, in bytes.
+    pub const MAX_NAME_LEN: usize = 32;
+    /// Most groups a player can own at once.
+    pub const MAX_OWNED: usize = 8;
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct GameSession {
     pub wordle_program_id: ActorId,
+    pub owner: ActorId,
+    pub config: GameSessionConfig,
+    pub reservations: Vec<GasReservation>,
     pub sessions: HashMap<ActorId, SessionInfo>,
+    pub results: Vec<GameResult>,
+    /// Hint fees collected and not withdrawn yet.
+    pub fees: u128,
+    /// Block a self-scheduled `Cleanup` is due at. Once it has passed, the cleanup either ran
+    /// or failed, and the next one can be scheduled.
+    pub cleanup_due: Option<u32>,
+    /// Groups by id.
+    pub groups: Vec<Group>,
+    pub stats: HashMap<ActorId, PlayerStats>,
+}
+
+impl GameSession {
+    /// Drops reservations that are no longer valid at `block_height`.
+    pub fn prune_reservations(&mut self, block_height: u32) {
+        self.reservations
+            .retain(|reservation| reservation.valid_until > block_height);
+    }
+
+    pub fn active_sessions(&self) -> usize {
+        self.sessions
+            .values()
+            .filter(|session_info| session_info.is_active())
+            .count()
+    }
+
+    /// Archives ranked sessions finished `archive_after` blocks before `block_height`, drops
+    /// such practice sessions and never-started ones, then prunes the oldest results beyond
+    /// `max_results`.
+    ///
+    /// Sessions with requests in flight are kept. Returns the number of archived and pruned
+    /// results.
+    pub fn cleanup(&mut self, block_height: u32) -> (u32, u32) {
+        let (archive_after, lock_timeout) = (self.config.archive_after, self.config.lock_timeout);
+        let mut archived = Vec::new();
+        self.sessions.retain(|user, session_info| {
+            if session_info.is_locked(block_height, lock_timeout) || !session_info.queue.is_empty()
+            {
+                return true;
+            }
+            match &session_info.session_status {
+                SessionStatus::Init => false,
+                SessionStatus::GameOver(..)
+                    if session_info.ended_block.saturating_add(archive_after) <= block_height =>
+                {
+                    if !session_info.is_practice() {
+                        archived.extend(session_info.result(*user));
+                    }
+                    false
+                }
+                _ => true,
+            }
+        });
+        archived.sort_by_key(|result| result.ended_block);
+        let archived_count = archived.len() as u32;
+        self.results.extend(archived);
+
+        let excess = self
+            .results
+            .len()
+            .saturating_sub(self.config.max_results as usize);
+        self.results.drain(..excess);
+        (archived_count, excess as u32)
+    }
 }
 
 impl From<&GameSession> for GameSessionState {
     fn from(game_session: &GameSession) -> Self {
         Self {
             wordle_program_id: game_session.wordle_program_id,
+            owner: game_session.owner,
+            config: game_session.config,
+            valid_reservations: game_session
+                .reservations
+                .iter()
+                .filter(|reservation| reservation.valid_until > exec::block_height())
+                .count() as u32,
+            fees: game_session.fees,
             game_sessions: game_session
                 .sessions
                 .iter()
                 .map(|(k, v)| (*k, v.clone()))
                 .collect(),
+            results: game_session.results.clone(),
+            groups: game_session.groups.clone(),
+            stats: game_session
+                .stats
+                .iter()
+                .map(|(user, stats)| (*user, *stats))
+                .collect(),
         }
     }
 }
@@ -0,0 +1,408 @@
+use game_session_io::*;
+use gstd::{
+    prelude::{Decode, Encode},
+    ActorId, MessageId,
+};
+use proptest::prelude::*;
+
+fn any_actor() -> impl Strategy<Value = ActorId> {
+    any::<[u8; 32]>().prop_map(ActorId::from)
+}
+
+fn any_message_id() -> impl Strategy<Value = MessageId> {
+    any::<[u8; 32]>().prop_map(MessageId::from)
+}
+
+fn any_config() -> impl Strategy<Value = GameSessionConfig> {
+    (
+        (any::<u8>(), any::<bool>()),
+        any::<u32>(),
+        any::<u64>(),
+        any::<u32>(),
+        any::<Option<u32>>(),
+        prop_oneof![
+            Just(BusyPolicy::Reject),
+            any::<u8>().prop_map(|limit| BusyPolicy::Queue { limit }),
+        ],
+        any::<u32>(),
+        any::<Option<u32>>(),
+        any::<u32>(),
+        any::<u32>(),
+        any::<Option<u32>>(),
+        prop_oneof![Just(HintCost::Try), any::<u128>().prop_map(HintCost::Fee)],
+    )
+        .prop_map(
+            |(
+                (tries_limit, hard_mode),
+                game_timeout,
+                reservation_amount,
+                reservation_duration,
+                move_timeout,
+                busy_policy,
+                lock_timeout,
+                max_active_sessions,
+                archive_after,
+                max_results,
+                cleanup_interval,
+                hint_cost,
+            )| GameSessionConfig {
+                tries_limit,
+                hard_mode,
+                game_timeout,
+                reservation_amount,
+                reservation_duration,
+                move_timeout,
+                busy_policy,
+                lock_timeout,
+                max_active_sessions,
+                archive_after,
+                max_results,
+                cleanup_interval,
+                hint_cost,
+            },
+        )
+}
+
+fn any_action() -> impl Strategy<Value = GameSessionAction> {
+    prop_oneof![
+        (
+            ".{0,12}",
+            prop_oneof![
+                Just(GameMode::Ranked),
+                any::<Option<u8>>().prop_map(|tries_limit| GameMode::Practice { tries_limit }),
+            ],
+        )
+            .prop_map(|(bank, mode)| GameSessionAction::StartGame { bank, mode }),
+        (".{0,8}", proptest::collection::vec(any::<[u8; 32]>(), 0..4))
+            .prop_map(|(word, proof)| GameSessionAction::CheckWord { word, proof }),
+        (any_actor(), any_message_id()).prop_map(|(user, session_id)| {
+            GameSessionAction::CheckGameStatus { user, session_id }
+        }),
+        (any_actor(), any_message_id(), any::<u8>()).prop_map(|(user, session_id, move_number)| {
+            GameSessionAction::CheckMoveStatus {
+                user,
+                session_id,
+                move_number,
+            }
+        }),
+        any::<u32>().prop_map(|count| GameSessionAction::TopUpReservations { count }),
+        any_config().prop_map(GameSessionAction::UpdateConfig),
+        Just(GameSessionAction::Cleanup),
+        Just(GameSessionAction::Reveal),
+        Just(GameSessionAction::GiveUp),
+        Just(GameSessionAction::RequestHint),
+        ".{0,40}".prop_map(|name| GameSessionAction::CreateGroup { name }),
+        (any::<u32>(), any_actor())
+            .prop_map(|(group_id, user)| GameSessionAction::InviteToGroup { group_id, user }),
+        any::<u32>().prop_map(|group_id| GameSessionAction::JoinGroup { group_id }),
+        any::<u32>().prop_map(|group_id| GameSessionAction::LeaveGroup { group_id }),
+        Just(GameSessionAction::WithdrawFees),
+    ]
+}
+
+fn any_wordle_action() -> impl Strategy<Value = WordleAction> {
+    prop_oneof![
+        (any_actor(), ".{0,12}", any::<bool>()).prop_map(|(user, bank, hard_mode)| {
+            WordleAction::StartGame {
+                user,
+                bank,
+                hard_mode,
+            }
+        }),
+        (
+            any_actor(),
+            ".{0,8}",
+            proptest::collection::vec(any::<[u8; 32]>(), 0..4)
+        )
+            .prop_map(|(user, word, proof)| WordleAction::CheckWord { user, word, proof }),
+        any_actor().prop_map(|user| WordleAction::RevealWord { user }),
+        (any_actor(), proptest::collection::vec(any::<u8>(), 0..5))
+            .prop_map(|(user, known)| WordleAction::RevealHint { user, known }),
+    ]
+}
+
+// The Wordle program's own action, which the mirror must encode byte for byte
+fn wordle_io_action(action: &WordleAction) -> wordle_io::Action {
+    match action.clone() {
+        WordleAction::StartGame {
+            user,
+            bank,
+            hard_mode,
+        } => wordle_io::Action::StartGame {
+            user,
+            bank,
+            hard_mode,
+        },
+        WordleAction::CheckWord { user, word, proof } => {
+            wordle_io::Action::CheckWord { user, word, proof }
+        }
+        WordleAction::RevealWord { user } => wordle_io::Action::RevealWord { user },
+        WordleAction::RevealHint { user, known } => wordle_io::Action::RevealHint { user, known },
+    }
+}
+
+// The Wordle program's own reply, which the mirror must decode
+fn wordle_io_event(event: &WordleEvent) -> wordle_io::Event {
+    match event.clone() {
+        WordleEvent::GameStarted { user } => wordle_io::Event::GameStarted { user },
+        WordleEvent::WordChecked {
+            user,
+            correct_positions,
+            contained_in_word,
+        } => wordle_io::Event::WordChecked {
+            user,
+            correct_positions,
+            contained_in_word,
+        },
+        WordleEvent::InvalidWord { user } => wordle_io::Event::InvalidWord { user },
+        WordleEvent::UnknownBank { user } => wordle_io::Event::UnknownBank { user },
+        WordleEvent::WordRevealed { user, word } => wordle_io::Event::WordRevealed { user, word },
+        WordleEvent::HintRevealed {
+            user,
+            position,
+            letter,
+        } => wordle_io::Event::HintRevealed {
+            user,
+            position,
+            letter,
+        },
+        WordleEvent::HardModeViolated { user, reason } => {
+            wordle_io::Event::HardModeViolated { user, reason }
+        }
+    }
+}
+
+fn any_wordle_event() -> impl Strategy<Value = WordleEvent> {
+    prop_oneof![
+        any_actor().prop_map(|user| WordleEvent::GameStarted { user }),
+        (
+            any_actor(),
+            proptest::collection::vec(any::<u8>(), 0..8),
+            proptest::collection::vec(any::<u8>(), 0..8),
+        )
+            .prop_map(|(user, correct_positions, contained_in_word)| {
+                WordleEvent::WordChecked {
+                    user,
+                    correct_positions,
+                    contained_in_word,
+                }
+            }),
+        any_actor().prop_map(|user| WordleEvent::InvalidWord { user }),
+        any_actor().prop_map(|user| WordleEvent::UnknownBank { user }),
+        (any_actor(), ".{0,8}").prop_map(|(user, word)| WordleEvent::WordRevealed { user, word }),
+        (any_actor(), any::<u8>(), ".{0,2}").prop_map(|(user, position, letter)| {
+            WordleEvent::HintRevealed {
+                user,
+                position,
+                letter,
+            }
+        }),
+        (any_actor(), ".{0,40}")
+            .prop_map(|(user, reason)| WordleEvent::HardModeViolated { user, reason }),
+    ]
+}
+
+fn any_lifecycle_event() -> impl Strategy<Value = LifecycleEvent> {
+    let feedback = || proptest::collection::vec(0u8..5, 0..5);
+    prop_oneof![
+        (any_actor(), any_message_id(), ".{0,12}", any::<u32>()).prop_map(
+            |(user, game_id, bank, deadline_block)| LifecycleEvent::GameStarted {
+                user,
+                game_id,
+                bank,
+                deadline_block,
+            }
+        ),
+        (any_actor(), any_message_id(), ".{0,8}").prop_map(|(user, game_id, word)| {
+            LifecycleEvent::GuessSubmitted {
+                user,
+                game_id,
+                word,
+            }
+        }),
+        (
+            any_actor(),
+            any_message_id(),
+            any::<u8>(),
+            feedback(),
+            feedback()
+        )
+            .prop_map(
+                |(user, game_id, tries, correct_positions, contained_in_word)| {
+                    LifecycleEvent::GuessEvaluated {
+                        user,
+                        game_id,
+                        tries,
+                        correct_positions,
+                        contained_in_word,
+                    }
+                }
+            ),
+        (any_actor(), any_message_id(), any::<u8>()).prop_map(|(user, game_id, tries)| {
+            LifecycleEvent::GameWon {
+                user,
+                game_id,
+                tries,
+            }
+        }),
+        (any_actor(), any_message_id(), any::<u8>()).prop_map(|(user, game_id, tries)| {
+            LifecycleEvent::GameLost {
+                user,
+                game_id,
+                tries,
+            }
+        }),
+        (any_actor(), any_message_id(), any::<Option<u8>>()).prop_map(
+            |(user, game_id, move_number)| LifecycleEvent::TimedOut {
+                user,
+                game_id,
+                move_number,
+            }
+        ),
+        any_config().prop_map(LifecycleEvent::ConfigChanged),
+        (any_actor(), any_message_id(), any::<u8>()).prop_map(|(user, game_id, tries)| {
+            LifecycleEvent::GaveUp {
+                user,
+                game_id,
+                tries,
+            }
+        }),
+        (any_actor(), any_message_id(), any::<u8>(), any::<u8>()).prop_map(
+            |(user, game_id, position, tries)| LifecycleEvent::HintRevealed {
+                user,
+                game_id,
+                position,
+                tries,
+            }
+        ),
+    ]
+}
+
+#[test]
+fn test_lifecycle_event_indices_are_stable() {
+    let (user, game_id) = (ActorId::zero(), MessageId::zero());
+    let events = [
+        LifecycleEvent::GameStarted {
+            user,
+            game_id,
+            bank: String::new(),
+            deadline_block: 0,
+        },
+        LifecycleEvent::GuessSubmitted {
+            user,
+            game_id,
+            word: String::new(),
+        },
+        LifecycleEvent::GuessEvaluated {
+            user,
+            game_id,
+            tries: 0,
+            correct_positions: vec![],
+            contained_in_word: vec![],
+        },
+        LifecycleEvent::GameWon {
+            user,
+            game_id,
+            tries: 0,
+        },
+        LifecycleEvent::GameLost {
+            user,
+            game_id,
+            tries: 0,
+        },
+        LifecycleEvent::TimedOut {
+            user,
+            game_id,
+            move_number: None,
+        },
+        LifecycleEvent::ConfigChanged(Default::default()),
+        LifecycleEvent::GaveUp {
+            user,
+            game_id,
+            tries: 0,
+        },
+        LifecycleEvent::HintRevealed {
+            user,
+            game_id,
+            position: 0,
+            tries: 0,
+        },
+    ];
+    for (index, event) in events.iter().enumerate() {
+        assert_eq!(event.encode()[0], index as u8);
+    }
+}
+
+fn any_payload() -> impl Strategy<Value = Vec<u8>> {
+    // Small values are likely to hit valid variant indices and lengths
+    proptest::collection::vec(prop_oneof![0u8..8, any::<u8>()], 0..96)
+}
+
+proptest! {
+    #[test]
+    fn prop_action_payload_round_trip(action in any_action()) {
+        let payload = action.encode();
+        let decoded = GameSessionAction::from_payload(&payload);
+        prop_assert_eq!(decoded.map(|action| action.encode()), Some(payload));
+    }
+
+    #[test]
+    fn prop_wordle_event_payload_round_trip(event in any_wordle_event()) {
+        prop_assert_eq!(WordleEvent::from_payload(&event.encode()), Some(event));
+    }
+
+    #[test]
+    fn prop_wordle_action_matches_wordle_io(action in any_wordle_action()) {
+        prop_assert_eq!(action.encode(), wordle_io_action(&action).encode());
+    }
+
+    #[test]
+    fn prop_wordle_event_matches_wordle_io(event in any_wordle_event()) {
+        let payload = wordle_io_event(&event).encode();
+        prop_assert_eq!(&event.encode(), &payload);
+        prop_assert_eq!(WordleEvent::from_payload(&payload), Some(event));
+    }
+
+    #[test]
+    fn prop_lifecycle_event_round_trip(event in any_lifecycle_event()) {
+        prop_assert_eq!(LifecycleEvent::decode(&mut &event.encode()[..]), Ok(event));
+    }
+
+    #[test]
+    fn prop_trailing_bytes_are_rejected(
+        action in any_action(),
+        event in any_wordle_event(),
+        junk in proptest::collection::vec(any::<u8>(), 1..8),
+    ) {
+        let mut payload = action.encode();
+        payload.extend(&junk);
+        prop_assert!(GameSessionAction::from_payload(&payload).is_none());
+
+        let mut payload = event.encode();
+        payload.extend(&junk);
+        prop_assert!(WordleEvent::from_payload(&payload).is_none());
+    }
+
+    #[test]
+    fn prop_truncated_payloads_are_rejected(action in any_action(), event in any_wordle_event()) {
+        let payload = action.encode();
+        for len in 0..payload.len() {
+            prop_assert!(GameSessionAction::from_payload(&payload[..len]).is_none());
+        }
+        let payload = event.encode();
+        for len in 0..payload.len() {
+            prop_assert!(WordleEvent::from_payload(&payload[..len]).is_none());
+        }
+    }
+
+    #[test]
+    fn prop_arbitrary_payloads_decode_canonically(payload in any_payload()) {
+        // Decoding never panics, and whatever is accepted encodes back to the same bytes
+        if let Some(action) = GameSessionAction::from_payload(&payload) {
+            prop_assert_eq!(action.encode(), payload.clone());
+        }
+        if let Some(event) = WordleEvent::from_payload(&payload) {
+            prop_assert_eq!(event.encode(), payload);
+        }
+    }
+}
@@ -1,9 +1,12 @@
 #![no_std]
 
 use game_session_io::*;
-use gstd::{exec, msg, debug};
-
-const TRIES_LIMIT: u8 = 5; // Maximum number of tries allowed in the game
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use gstd::{collections::HashMap, debug, exec, msg, prelude::*, ActorId, MessageId, ReservationId};
 
 static mut GAME_SESSION_STATE: Option<GameSession> = None;
 
@@ -11,156 +14,840 @@ static mut GAME_SESSION_STATE: Option<GameSession> = None;
 extern "C" fn init() {
     let game_session_init: GameSessionInit = msg::load().expect("Unable to decode `GameSessionInit`");
     game_session_init.assert_valid(); // Validate the initialization data
-    unsafe { GAME_SESSION_STATE = Some(game_session_init.into()) }; // Initialize the game session state
+    let game_session = GameSession {
+        owner: msg::source(), // The deployer manages the gas reservations
+        ..game_session_init.into()
+    };
+    unsafe { GAME_SESSION_STATE = Some(game_session) }; // Initialize the game session state
 }
 
-#[no_mangle]
-extern "C" fn handle() {
-    let game_session_action: GameSessionAction = msg::load().expect("Unable to decode `GameSessionAction`");
-    let game_session = unsafe {
+// Take a valid reservation from the pool, or reserve a new one from the current message's gas
+fn take_reservation(game_session: &mut GameSession) -> GasReservation {
+    game_session.prune_reservations(exec::block_height());
+    match game_session.reservations.pop() {
+        Some(reservation) => reservation,
+        None => GasReservation {
+            id: exec::reserve_gas(
+                game_session.config.reservation_amount,
+                game_session.config.reservation_duration,
+            )
+            .expect("Unable to reserve gas for the timeout message"),
+            valid_until: exec::block_height() + game_session.config.reservation_duration,
+        },
+    }
+}
+
+// Take the reservation of a timer the request may schedule, before it waits for Wordle: a lack
+// of gas then fails the request before its lock on the session is saved
+fn reserve_timer(game_session: &mut GameSession, needed: bool) -> Option<GasReservation> {
+    needed.then(|| take_reservation(game_session))
+}
+
+// Send a delayed status check to the program itself, paid from a gas reservation
+fn schedule_check(reservation_id: ReservationId, action: GameSessionAction, delay: u32) {
+    msg::send_delayed_from_reservation(reservation_id, exec::program_id(), action, 0, delay)
+        .expect("Error in send_delayed a message");
+}
+
+// Schedule the whole-game timeout check with the reservation taken for it
+fn schedule_timeout(
+    game_session: &GameSession,
+    reservation: Option<GasReservation>,
+    user: ActorId,
+    session_id: MessageId,
+) {
+    if let Some(reservation) = reservation {
+        schedule_check(
+            reservation.id,
+            GameSessionAction::CheckGameStatus { user, session_id },
+            game_session.config.game_timeout,
+        );
+    }
+}
+
+// Schedule the per-move timeout check with the reservation taken for it, giving the reservation
+// back to the pool if the move timer was disabled meanwhile
+fn schedule_move_timeout(
+    game_session: &mut GameSession,
+    reservation: Option<GasReservation>,
+    user: ActorId,
+    session_id: MessageId,
+    move_number: u8,
+) {
+    let Some(reservation) = reservation else {
+        return;
+    };
+    match game_session.config.move_timeout {
+        Some(move_timeout) => schedule_check(
+            reservation.id,
+            GameSessionAction::CheckMoveStatus {
+                user,
+                session_id,
+                move_number,
+            },
+            move_timeout,
+        ),
+        None => game_session.reservations.push(reservation),
+    }
+}
+
+// Schedule the next self cleanup if the config asks for them and none is on its way
+fn schedule_cleanup(game_session: &mut GameSession) {
+    let Some(cleanup_interval) = game_session.config.cleanup_interval else {
+        return;
+    };
+    // A cleanup that failed leaves its due block behind, which no longer holds the next one
+    if game_session
+        .cleanup_due
+        .is_some_and(|due| due >= exec::block_height())
+    {
+        return;
+    }
+    // Only pooled reservations pay for cleanups, so a lack of gas never fails the caller;
+    // the next top-up, config update or owner cleanup schedules it then
+    game_session.prune_reservations(exec::block_height());
+    let Some(reservation) = game_session.reservations.pop() else {
+        return;
+    };
+    msg::send_delayed_from_reservation(
+        reservation.id,
+        exec::program_id(),
+        GameSessionAction::Cleanup,
+        0,
+        cleanup_interval,
+    )
+    .expect("Error in send_delayed a message");
+    game_session.cleanup_due = Some(exec::block_height() + cleanup_interval);
+}
+
+// Broadcast a lifecycle event for indexers and frontends
+fn emit(event: LifecycleEvent) {
+    msg::send(ActorId::zero(), event, 0).expect("Error in emitting an event");
+}
+
+fn game_session_mut() -> &'static mut GameSession {
+    unsafe {
         GAME_SESSION_STATE
             .as_mut()
             .expect("Game is not initialized")
+    }
+}
+
+fn group_mut(group_id: u32) -> &'static mut Group {
+    game_session_mut()
+        .groups
+        .get_mut(group_id as usize)
+        .expect("There is no such group")
+}
+
+// Count the user's finished game in their stats unless it was a practice game
+fn record_stats(
+    stats: &mut HashMap<ActorId, PlayerStats>,
+    user: ActorId,
+    session_info: &SessionInfo,
+) {
+    if let Some(result) = session_info.result(user).filter(|_| !session_info.is_practice()) {
+        stats.entry(user).or_default().record(&result);
+    }
+}
+
+// Ask the Wordle program for the secret word of the user's finished game and end it there
+async fn reveal_word(wordle_program_id: ActorId, user: ActorId) -> Option<String> {
+    msg::send_for_reply(wordle_program_id, WordleAction::RevealWord { user }, 0, 0)
+        .expect("Error in sending a message")
+        .await
+        .ok()
+        .and_then(|payload| WordleEvent::from_payload(&payload))
+        // Replies about another user or to another request are as good as no reply
+        .and_then(|event| match event {
+            WordleEvent::WordRevealed { user: revealed, word } if revealed == user => Some(word),
+            _ => None,
+        })
+}
+
+// End the user's game with a loss if the timer still belongs to the current session
+async fn expire_session(
+    user: ActorId,
+    move_number: Option<u8>,
+    is_current: impl FnOnce(&SessionInfo) -> bool,
+) {
+    let game_session = game_session_mut();
+    let Some(session_info) = game_session.sessions.get_mut(&user) else {
+        return;
     };
-    match game_session_action {
-        // Handle the StartGame action
-        GameSessionAction::StartGame => {
-            let user = msg::source(); // Get the message sender (player)
-            let session_info = game_session.sessions.entry(user).or_default(); // Get or create session information for the user
-            debug!("handle:{:?}", session_info); // Debug log the session information
-            match &session_info.session_status {
-                // Check the session status and proceed accordingly
-                SessionStatus::Init | SessionStatus::GameOver(..) | SessionStatus::WaitWordleStartReply => {
-                    // Send a StartGame message to the Wordle program
-                    let send_to_wordle_msg_id = msg::send(
-                        game_session.wordle_program_id,
-                        WordleAction::StartGame { user },
-                        0,
-                    )
-                    .expect("Error in sending a message");
-
-                    // Update session information
-                    session_info.session_id = msg::id();
-                    session_info.original_msg_id = msg::id();
-                    session_info.send_to_wordle_msg_id = send_to_wordle_msg_id;
-                    session_info.tries = 0;
-                    session_info.session_status = SessionStatus::WaitWordleStartReply;
-
-                    exec::wait();
+    let transition = Transition::Finish(GameStatus::Lose);
+    // Outdated timers, finished games and practice games, which have no timers, are ignored
+    if !is_current(session_info)
+        || session_info.is_practice()
+        || session_info.session_status.next(&transition).is_none()
+    {
+        return;
+    }
+    session_info.apply(transition);
+    session_info.ended_block = exec::block_height();
+    record_stats(&mut game_session.stats, user, session_info);
+    emit(LifecycleEvent::TimedOut {
+        user,
+        game_id: session_info.session_id,
+        move_number,
+    });
 
-                }
-                SessionStatus::ReplyReceived(_wordle_event) => {
-                    // Handle the case where a `ReplyReceived` state exists
-                    // In this case, it means the StartGame has already received a reply from Wordle
-                        session_info.session_status = SessionStatus::WaitUserInput;
-                    // Send a delayed message to check the game status after a delay
-                    msg::send_delayed(
-                        exec::program_id(),
-                        GameSessionAction::CheckGameStatus {
-                            user,
-                            session_id: msg::id(),
-                        },
-                        0,
-                        200,
-                    )
-                    .expect("Error in send_delayed a message");
-
-                        msg::reply(GameSessionEvent::StartSuccess, 0)
-                            .expect("Failed to send a reply");
-                    }
-
-                SessionStatus::WaitUserInput | SessionStatus::WaitWordleCheckWordReply => {
-                    panic!("The user is already in a game");
-                }
+    // Wordle handles a guess in flight before the reveal, which ends its game
+    let word = reveal_word(game_session.wordle_program_id, user).await;
+    let event = GameSessionEvent::GameOver {
+        status: GameStatus::Lose,
+        word,
+    };
+    msg::send(user, event, 0).expect("Error in sending a reply");
+}
+
+// Resolves once the current message holds the user's session lock
+struct SessionLock {
+    user: ActorId,
+}
+
+impl Future for SessionLock {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        let msg_id = msg::id();
+        let game_session = game_session_mut();
+        let lock_timeout = game_session.config.lock_timeout;
+        let session_info = game_session.sessions.entry(self.user).or_default();
+        if session_info.is_locked(exec::block_height(), lock_timeout) {
+            // Wait until `unlock_session` wakes this message
+            if !session_info.queue.contains(&msg_id) {
+                session_info.queue.push(msg_id);
             }
+            Poll::Pending
+        } else {
+            session_info.queue.retain(|id| *id != msg_id);
+            // An expired lock is taken over, so a failed request cannot block the player forever
+            session_info.take_lock(msg_id, exec::block_height());
+            Poll::Ready(())
         }
-        // Handle the CheckWord action
-        GameSessionAction::CheckWord { word } => {
-            let user = msg::source(); // Get the message sender (player)
-            let session_info = game_session.sessions.entry(user).or_default(); // Get or create session information for the user
-            match &session_info.session_status {
-                // Handle the case where a reply has been received from the Wordle program
-                SessionStatus::ReplyReceived(wordle_event) => {
-                    session_info.tries += 1; // Increment the number of tries
-                    if wordle_event.has_guessed() {
-                        // If the word is guessed correctly, the game is over with a win
-                        session_info.session_status = SessionStatus::GameOver(GameStatus::Win);
-                        msg::reply(GameSessionEvent::GameOver(GameStatus::Win), 0)
-                            .expect("Failed to send a reply");
-                    } else if session_info.tries == TRIES_LIMIT {
-                        // If the maximum number of tries is reached, the game is over with a loss
-                        session_info.session_status = SessionStatus::GameOver(GameStatus::Lose);
-                        msg::reply(GameSessionEvent::GameOver(GameStatus::Lose), 0)
-                            .expect("Failed to send a reply");
-                    } else {
-                        // Otherwise, reply with the event and update the status to wait for user input
-                        msg::reply::<GameSessionEvent>(wordle_event.into(), 0)
-                            .expect("Failed to send a reply");
-                        session_info.session_status = SessionStatus::WaitUserInput;
-                    }
-                }
-                // Handle the case where the user is providing a word input
-                SessionStatus::WaitUserInput => {
-                    // Validate the word (must be 5 lowercase letters)
-                    assert!(
-                        word.len() == 5 && word.chars().all(|c| c.is_lowercase()),
-                        "Invalid word"
-                    );
-                    // Send the word to the Wordle program for checking
-                    let send_to_wordle_msg_id = msg::send(
-                        game_session.wordle_program_id,
-                        WordleAction::CheckWord { user, word },
-                        0,
-                    )
-                    .expect("Error in sending a message");
-
-                    // Update session information and set the status to wait for the Wordle check reply
-                    session_info.original_msg_id = msg::id();
-                    session_info.send_to_wordle_msg_id = send_to_wordle_msg_id;
-                    session_info.session_status = SessionStatus::WaitWordleCheckWordReply;
-
-                    exec::wait(); // Wait for a reply
-                }
-                _ => {
-                    panic!("Invalid state or the user is not in the game");
-                }
+    }
+}
+
+// Take the user's session lock, queueing or rejecting the request according to the busy policy
+async fn lock_session(user: ActorId) -> bool {
+    let game_session = game_session_mut();
+    let GameSessionConfig {
+        busy_policy,
+        lock_timeout,
+        ..
+    } = game_session.config;
+    let session_info = game_session.sessions.entry(user).or_default();
+    if session_info.is_locked(exec::block_height(), lock_timeout) {
+        match busy_policy {
+            BusyPolicy::Reject => return false,
+            BusyPolicy::Queue { limit } if session_info.queue.len() >= limit as usize => {
+                return false
             }
+            BusyPolicy::Queue { .. } => {}
         }
-        // Handle the CheckGameStatus action (for checking the game status after a delay)
-        GameSessionAction::CheckGameStatus { user, session_id } => {
-            if msg::source() == exec::program_id() {
-                if let Some(session_info) = game_session.sessions.get_mut(&user) {
-                    // If the session ID matches and the game is not over, set the status to game over with a loss
-                    if session_id == session_info.session_id
-                        && !matches!(session_info.session_status, SessionStatus::GameOver(..))
-                    {
-                        session_info.session_status = SessionStatus::GameOver(GameStatus::Lose);
-                        msg::send(user, GameSessionEvent::GameOver(GameStatus::Lose), 0)
-                            .expect("Error in sending a reply");
-                    }
-                }
-            }
+    }
+    SessionLock { user }.await;
+    true
+}
+
+// Release the user's session lock and wake the next queued request
+fn unlock_session(session_info: &mut SessionInfo) {
+    // A request whose expired lock was taken over no longer holds it
+    if session_info.lock != Some(msg::id()) {
+        return;
+    }
+    session_info.lock = None;
+    if !session_info.queue.is_empty() {
+        let next = session_info.queue.remove(0);
+        // The message may already have been woken by its wait timeout
+        let _ = exec::wake(next);
+    }
+}
+
+async fn start_game(bank: String, mode: GameMode) {
+    let user = msg::source(); // Get the message sender (player)
+    assert!(
+        mode != GameMode::Practice { tries_limit: Some(0) },
+        "Invalid tries_limit"
+    );
+    if !lock_session(user).await {
+        msg::reply(GameSessionEvent::SessionBusy, 0).expect("Failed to send a reply");
+        return;
+    }
+    let game_session = game_session_mut();
+    let at_capacity = game_session
+        .config
+        .max_active_sessions
+        .is_some_and(|max_active_sessions| game_session.active_sessions() >= max_active_sessions as usize);
+    let session_info = game_session.sessions.entry(user).or_default(); // Get or create session information for the user
+    // A player already in a game is refused by the transition below instead
+    if at_capacity && !session_info.is_active() {
+        unlock_session(session_info);
+        msg::reply(GameSessionEvent::TooManySessions, 0).expect("Failed to send a reply");
+        return;
+    }
+    debug!("start_game:{:?}", session_info); // Debug log the session information
+    session_info.apply(Transition::Start); // Fails if the user is already in a game
+    session_info.session_id = msg::id();
+    session_info.tries = 0;
+    session_info.bank = bank.clone();
+    session_info.mode = mode;
+    session_info.tries_limit = mode.tries_limit(game_session.config.tries_limit);
+    session_info.found_positions.clear();
+    session_info.hints.clear();
+    session_info.guesses.clear();
+    let ranked = !session_info.is_practice();
+    let move_timer = ranked && game_session.config.move_timeout.is_some();
+    let timeout_reservation = reserve_timer(game_session, ranked);
+    let move_reservation = reserve_timer(game_session, move_timer);
+
+    // Ask the Wordle program to start a game and wait for its reply
+    let reply = msg::send_for_reply(
+        game_session.wordle_program_id,
+        WordleAction::StartGame {
+            user,
+            bank,
+            hard_mode: game_session.config.hard_mode,
+        },
+        0,
+        0,
+    )
+    .expect("Error in sending a message")
+    .await
+    .ok()
+    .and_then(|payload| WordleEvent::from_payload(&payload))
+    // Replies about another user or to another request are as good as no reply
+    .filter(|event| {
+        matches!(event, WordleEvent::GameStarted { .. } | WordleEvent::UnknownBank { .. })
+            && *event.get_user() == user
+    });
+
+    // Other messages may have been handled while waiting, so look the session up again
+    let game_session = game_session_mut();
+    let session_info = game_session
+        .sessions
+        .get_mut(&user)
+        .expect("The user is not in the game");
+    unlock_session(session_info);
+    let Some(wordle_event) = reply else {
+        session_info.apply(Transition::Cancel);
+        // No timers are scheduled, so their reservations go back to the pool
+        game_session.reservations.extend(timeout_reservation);
+        game_session.reservations.extend(move_reservation);
+        msg::reply(GameSessionEvent::RequestFailed, 0).expect("Failed to send a reply");
+        return;
+    };
+    if let WordleEvent::UnknownBank { .. } = wordle_event {
+        session_info.apply(Transition::Cancel);
+        game_session.reservations.extend(timeout_reservation);
+        game_session.reservations.extend(move_reservation);
+        msg::reply(GameSessionEvent::UnknownBank, 0).expect("Failed to send a reply");
+        return;
+    }
+
+    session_info.apply(Transition::Started);
+    let session_id = session_info.session_id;
+    if session_info.is_practice() {
+        // Practice games have no timers and count for nothing
+        session_info.deadline_block = u32::MAX;
+        let reply = session_info.reply_event(&wordle_event);
+        msg::reply(reply, 0).expect("Failed to send a reply");
+        return;
+    }
+    session_info.deadline_block = exec::block_height() + game_session.config.game_timeout;
+    let reply = session_info.reply_event(&wordle_event);
+    emit(LifecycleEvent::GameStarted {
+        user,
+        game_id: session_id,
+        bank: session_info.bank.clone(),
+        deadline_block: session_info.deadline_block,
+    });
+
+    // Send delayed messages to check the game status after a delay
+    schedule_timeout(game_session, timeout_reservation, user, session_id);
+    schedule_move_timeout(game_session, move_reservation, user, session_id, 0);
+
+    msg::reply(reply, 0).expect("Failed to send a reply");
+}
+
+async fn check_word(word: String, proof: Vec<[u8; 32]>) {
+    let user = msg::source(); // Get the message sender (player)
+    if !lock_session(user).await {
+        msg::reply(GameSessionEvent::SessionBusy, 0).expect("Failed to send a reply");
+        return;
+    }
+    let game_session = game_session_mut();
+    let session_info = game_session
+        .sessions
+        .get_mut(&user)
+        .expect("The user is not in the game");
+    session_info.apply(Transition::Submit); // Fails unless the session waits for user input
+    if !session_info.is_practice() {
+        emit(LifecycleEvent::GuessSubmitted {
+            user,
+            game_id: session_info.session_id,
+            word: word.clone(),
+        });
+    }
+    let move_timer = !session_info.is_practice() && game_session.config.move_timeout.is_some();
+    let move_reservation = reserve_timer(game_session, move_timer);
+
+    // Send the word to the Wordle program for checking and wait for its reply
+    let reply = msg::send_for_reply(
+        game_session.wordle_program_id,
+        WordleAction::CheckWord { user, word, proof },
+        0,
+        0,
+    )
+    .expect("Error in sending a message")
+    .await
+    .ok()
+    .and_then(|payload| WordleEvent::from_payload(&payload))
+    // Replies about another user or to another request are as good as no reply
+    .filter(|event| {
+        matches!(
+            event,
+            WordleEvent::WordChecked { .. }
+                | WordleEvent::InvalidWord { .. }
+                | WordleEvent::HardModeViolated { .. }
+        ) && *event.get_user() == user
+    });
+
+    let game_session = game_session_mut();
+    let session_info = game_session
+        .sessions
+        .get_mut(&user)
+        .expect("The user is not in the game");
+    // A timer may have ended the game while waiting for Wordle
+    if let SessionStatus::GameOver(status) = &session_info.session_status {
+        unlock_session(session_info);
+        game_session.reservations.extend(move_reservation);
+        let event = GameSessionEvent::GameOver {
+            status: status.clone(),
+            word: None,
+        };
+        msg::reply(event, 0).expect("Failed to send a reply");
+        return;
+    }
+    let Some(wordle_event) = reply else {
+        unlock_session(session_info);
+        session_info.apply(Transition::Cancel);
+        game_session.reservations.extend(move_reservation);
+        msg::reply(GameSessionEvent::RequestFailed, 0).expect("Failed to send a reply");
+        return;
+    };
+
+    // Wordle validates the word against its alphabet and, in hard mode, the previous hints;
+    // a refused word does not use a try
+    if matches!(
+        wordle_event,
+        WordleEvent::InvalidWord { .. } | WordleEvent::HardModeViolated { .. }
+    ) {
+        unlock_session(session_info);
+        session_info.apply(Transition::Cancel);
+        game_session.reservations.extend(move_reservation);
+        msg::reply(session_info.reply_event(&wordle_event), 0).expect("Failed to send a reply");
+        return;
+    }
+
+    session_info.tries = session_info.tries.saturating_add(1); // Increment the number of tries
+    let (game_id, tries) = (session_info.session_id, session_info.tries);
+    // Practice games emit no lifecycle events
+    let ranked = !session_info.is_practice();
+    if let WordleEvent::WordChecked {
+        correct_positions,
+        contained_in_word,
+        ..
+    } = &wordle_event
+    {
+        session_info.add_found_positions(correct_positions);
+        session_info.guesses.push(GuessColours {
+            correct_positions: correct_positions.clone(),
+            contained_in_word: contained_in_word.clone(),
+        });
+        if ranked {
+            emit(LifecycleEvent::GuessEvaluated {
+                user,
+                game_id,
+                tries,
+                correct_positions: correct_positions.clone(),
+                contained_in_word: contained_in_word.clone(),
+            });
         }
     }
+    if wordle_event.has_guessed() {
+        // If the word is guessed correctly, the game is over with a win
+        unlock_session(session_info);
+        session_info.apply(Transition::Finish(GameStatus::Win));
+        game_session.reservations.extend(move_reservation);
+        session_info.ended_block = exec::block_height();
+        record_stats(&mut game_session.stats, user, session_info);
+        let event = GameSessionEvent::GameOver {
+            status: GameStatus::Win,
+            word: None,
+        };
+        msg::reply(event, 0).expect("Failed to send a reply");
+        if ranked {
+            emit(LifecycleEvent::GameWon { user, game_id, tries });
+        }
+    } else if session_info.is_out_of_tries() {
+        // If the maximum number of tries is reached, the game is over with a loss
+        session_info.apply(Transition::Finish(GameStatus::Lose));
+        game_session.reservations.extend(move_reservation);
+        session_info.ended_block = exec::block_height();
+        record_stats(&mut game_session.stats, user, session_info);
+        if ranked {
+            emit(LifecycleEvent::GameLost { user, game_id, tries });
+        }
+        // The lock is kept until Wordle reveals the word, so no new game starts before
+        let word = reveal_word(game_session.wordle_program_id, user).await;
+        let session_info = game_session_mut()
+            .sessions
+            .get_mut(&user)
+            .expect("The user is not in the game");
+        unlock_session(session_info);
+        let event = GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word,
+        };
+        msg::reply(event, 0).expect("Failed to send a reply");
+    } else {
+        // Otherwise, reply with the event and wait for the next guess
+        unlock_session(session_info);
+        session_info.apply(Transition::Checked);
+        msg::reply(session_info.reply_event(&wordle_event), 0)
+            .expect("Failed to send a reply");
+        // Restart the move timer for the next guess
+        schedule_move_timeout(game_session, move_reservation, user, game_id, tries);
+    }
 }
 
-#[no_mangle]
-extern "C" fn handle_reply() {
-    // Handle the reply message from the Wordle program
-    let reply_to = msg::reply_to().expect("Failed to query reply_to data");
-    let wordle_event: WordleEvent = msg::load().expect("Unable to decode WordleEvent");
-    let game_session = unsafe {
-        GAME_SESSION_STATE
-            .as_mut()
-            .expect("Game is not initialized")
+// End the player's game with a loss and ask Wordle for its word: `Reveal` only gives up
+// practice games and replies `WordRevealed`, `GiveUp` gives up any game and replies `GameOver`
+async fn give_up(practice_only: bool) {
+    let user = msg::source(); // Get the message sender (player)
+    if !lock_session(user).await {
+        msg::reply(GameSessionEvent::SessionBusy, 0).expect("Failed to send a reply");
+        return;
+    }
+    let game_session = game_session_mut();
+    let session_info = game_session.sessions.entry(user).or_default();
+    let in_game = session_info.session_status == SessionStatus::WaitUserInput;
+    if !in_game || (practice_only && !session_info.is_practice()) {
+        unlock_session(session_info);
+        let event = if practice_only {
+            GameSessionEvent::NotPractice
+        } else {
+            GameSessionEvent::NoGame
+        };
+        msg::reply(event, 0).expect("Failed to send a reply");
+        return;
+    }
+    // The game is over even if Wordle does not answer; pending timers find it finished
+    session_info.apply(Transition::Finish(GameStatus::Lose));
+    session_info.ended_block = exec::block_height();
+    record_stats(&mut game_session.stats, user, session_info);
+    if !session_info.is_practice() {
+        emit(LifecycleEvent::GaveUp {
+            user,
+            game_id: session_info.session_id,
+            tries: session_info.tries,
+        });
+    }
+
+    let word = reveal_word(game_session.wordle_program_id, user).await;
+
+    let session_info = game_session_mut()
+        .sessions
+        .get_mut(&user)
+        .expect("The user is not in the game");
+    unlock_session(session_info);
+    // Without Wordle's answer a revealed practice game ends like any given up game
+    let reply = match (practice_only, word) {
+        (true, Some(word)) => GameSessionEvent::WordRevealed { word },
+        (_, word) => GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word,
+        },
+    };
+    msg::reply(reply, 0).expect("Failed to send a reply");
+}
+
+async fn request_hint() {
+    let user = msg::source(); // Get the message sender (player)
+    let value = msg::value();
+    if !lock_session(user).await {
+        msg::reply(GameSessionEvent::SessionBusy, value).expect("Failed to send a reply");
+        return;
+    }
+    let game_session = game_session_mut();
+    let config = game_session.config;
+    let session_info = game_session.sessions.entry(user).or_default();
+    let fee = match config.hint_cost {
+        // A hint cannot use the last try, which would end the game without a guess
+        HintCost::Try if session_info.tries_left() > 1 => Some(0),
+        HintCost::Fee(fee) if value >= fee => Some(fee),
+        _ => None,
+    };
+    let Some(fee) = fee.filter(|_| session_info.can_hint()) else {
+        unlock_session(session_info);
+        msg::reply(GameSessionEvent::HintUnavailable, value).expect("Failed to send a reply");
+        return;
     };
-    let user = wordle_event.get_user(); // Get the user from the Wordle event
+    let known = session_info.found_positions.clone();
+    // A hint that costs a try is a move, so the move timer restarts
+    let move_timer = config.hint_cost == HintCost::Try
+        && !session_info.is_practice()
+        && config.move_timeout.is_some();
+    let move_reservation = reserve_timer(game_session, move_timer);
 
-    if let Some(session_info) = game_session.sessions.get_mut(user) {
-        // If the reply matches the expected message ID and the session is waiting for a reply
-        if reply_to == session_info.send_to_wordle_msg_id && session_info.is_wait_reply_status() {
-            session_info.session_status = SessionStatus::ReplyReceived(wordle_event); // Update the status to ReplyReceived
-            exec::wake(session_info.original_msg_id).expect("Failed to wake the message"); // Wake up the waiting logic
+    // Ask the Wordle program for a letter at a position not found yet and wait for its reply
+    let reply = msg::send_for_reply(
+        game_session.wordle_program_id,
+        WordleAction::RevealHint { user, known },
+        0,
+        0,
+    )
+    .expect("Error in sending a message")
+    .await
+    .ok()
+    .and_then(|payload| WordleEvent::from_payload(&payload))
+    // Replies about another user or to another request are as good as no reply
+    .filter(|event| matches!(event, WordleEvent::HintRevealed { .. }) && *event.get_user() == user);
+
+    let game_session = game_session_mut();
+    let session_info = game_session
+        .sessions
+        .get_mut(&user)
+        .expect("The user is not in the game");
+    unlock_session(session_info);
+    // A timer may have ended the game while waiting for Wordle; the hint is not charged then
+    if let SessionStatus::GameOver(status) = &session_info.session_status {
+        game_session.reservations.extend(move_reservation);
+        let event = GameSessionEvent::GameOver {
+            status: status.clone(),
+            word: None,
+        };
+        msg::reply(event, value).expect("Failed to send a reply");
+        return;
+    }
+    let Some(WordleEvent::HintRevealed {
+        position, letter, ..
+    }) = reply
+    else {
+        game_session.reservations.extend(move_reservation);
+        msg::reply(GameSessionEvent::RequestFailed, value).expect("Failed to send a reply");
+        return;
+    };
+
+    if config.hint_cost == HintCost::Try {
+        session_info.tries = session_info.tries.saturating_add(1);
+    }
+    session_info.add_found_positions(&[position]);
+    session_info.hints.push(Hint {
+        position,
+        letter: letter.clone(),
+    });
+    game_session.fees += fee;
+    let (game_id, tries) = (session_info.session_id, session_info.tries);
+    let event = GameSessionEvent::HintRevealed {
+        game_id,
+        position,
+        letter,
+        tries_left: session_info.tries_left(),
+    };
+    msg::reply(event, value - fee).expect("Failed to send a reply");
+    if !session_info.is_practice() {
+        emit(LifecycleEvent::HintRevealed {
+            user,
+            game_id,
+            position,
+            tries,
+        });
+    }
+    schedule_move_timeout(game_session, move_reservation, user, game_id, tries);
+}
+
+#[gstd::async_main]
+async fn main() {
+    let payload = msg::load_bytes().expect("Unable to load the payload");
+    let Some(game_session_action) = GameSessionAction::from_payload(&payload) else {
+        msg::reply(GameSessionEvent::InvalidPayload, 0).expect("Failed to send a reply");
+        return;
+    };
+    match game_session_action {
+        // Handle the StartGame action
+        GameSessionAction::StartGame { bank, mode } => start_game(bank, mode).await,
+        // Handle the CheckWord action
+        GameSessionAction::CheckWord { word, proof } => check_word(word, proof).await,
+        // Handle the CheckGameStatus action (for checking the game status after a delay)
+        GameSessionAction::CheckGameStatus { user, session_id } => {
+            if msg::source() == exec::program_id() {
+                expire_session(user, None, |session_info| {
+                    session_info.session_id == session_id
+                })
+                .await;
+            }
+        }
+        // Handle the CheckMoveStatus action (the player did not guess in time)
+        GameSessionAction::CheckMoveStatus {
+            user,
+            session_id,
+            move_number,
+        } => {
+            if msg::source() == exec::program_id() {
+                // Timers for already answered moves are outdated
+                expire_session(user, Some(move_number), |session_info| {
+                    session_info.session_id == session_id && session_info.tries == move_number
+                })
+                .await;
+            }
+        }
+        // Handle the TopUpReservations action (owner reserves gas for future timeouts)
+        GameSessionAction::TopUpReservations { count } => {
+            let game_session = game_session_mut();
+            assert_eq!(msg::source(), game_session.owner, "Only the owner can top up reservations");
+            game_session.prune_reservations(exec::block_height());
+            let valid_until = exec::block_height() + game_session.config.reservation_duration;
+            for _ in 0..count {
+                let id = exec::reserve_gas(
+                    game_session.config.reservation_amount,
+                    game_session.config.reservation_duration,
+                )
+                .expect("Unable to reserve gas");
+                game_session.reservations.push(GasReservation { id, valid_until });
+            }
+            schedule_cleanup(game_session);
+            msg::reply(
+                GameSessionEvent::ReservationsToppedUp {
+                    valid_reservations: game_session.reservations.len() as u32,
+                },
+                0,
+            )
+            .expect("Failed to send a reply");
+        }
+        // Handle the UpdateConfig action (owner replaces the whole config)
+        GameSessionAction::UpdateConfig(config) => {
+            let game_session = game_session_mut();
+            assert_eq!(msg::source(), game_session.owner, "Only the owner can update the config");
+            config.assert_valid();
+            game_session.config = config;
+            schedule_cleanup(game_session);
+            msg::reply(GameSessionEvent::ConfigUpdated(config), 0)
+                .expect("Failed to send a reply");
+            emit(LifecycleEvent::ConfigChanged(config));
+        }
+        // Handle the Cleanup action (archive finished sessions and prune old results)
+        GameSessionAction::Cleanup => {
+            let game_session = game_session_mut();
+            let scheduled = msg::source() == exec::program_id();
+            assert!(
+                scheduled || msg::source() == game_session.owner,
+                "Only the owner can clean up sessions"
+            );
+            let (archived, pruned) = game_session.cleanup(exec::block_height());
+            if scheduled {
+                game_session.cleanup_due = None;
+            } else {
+                msg::reply(GameSessionEvent::CleanedUp { archived, pruned }, 0)
+                    .expect("Failed to send a reply");
+            }
+            schedule_cleanup(game_session);
+        }
+        // Handle the Reveal action (the player gives up a practice game)
+        GameSessionAction::Reveal => give_up(true).await,
+        // Handle the GiveUp action (the player ends any game as a loss)
+        GameSessionAction::GiveUp => give_up(false).await,
+        // Handle the RequestHint action (the player pays for a letter of the word)
+        GameSessionAction::RequestHint => request_hint().await,
+        // Handle the CreateGroup action (the player starts a group of friends)
+        GameSessionAction::CreateGroup { name } => {
+            assert!(
+                !name.is_empty() && name.len() <= Group::MAX_NAME_LEN,
+                "Invalid group name"
+            );
+            let game_session = game_session_mut();
+            let owned = game_session
+                .groups
+                .iter()
+                .filter(|group| group.owner == msg::source())
+                .count();
+            assert!(owned < Group::MAX_OWNED, "Too many groups");
+            let group = Group {
+                name,
+                owner: msg::source(),
+                members: vec![msg::source()],
+                invited: Vec::new(),
+            };
+            // Deleted groups have no members, and their ids are reused
+            let group_id = match game_session
+                .groups
+                .iter()
+                .position(|group| group.members.is_empty())
+            {
+                Some(index) => {
+                    game_session.groups[index] = group;
+                    index
+                }
+                None => {
+                    game_session.groups.push(group);
+                    game_session.groups.len() - 1
+                }
+            } as u32;
+            msg::reply(GameSessionEvent::GroupCreated { group_id }, 0)
+                .expect("Failed to send a reply");
+        }
+        // Handle the InviteToGroup action (a member invites another player)
+        GameSessionAction::InviteToGroup { group_id, user } => {
+            let group = group_mut(group_id);
+            assert!(group.members.contains(&msg::source()), "Only members can invite");
+            assert!(!group.members.contains(&user), "The user is already a member");
+            if !group.invited.contains(&user) {
+                group.invited.push(user);
+            }
+            msg::reply(GameSessionEvent::InvitedToGroup { group_id, user }, 0)
+                .expect("Failed to send a reply");
+        }
+        // Handle the JoinGroup action (the player accepts an invitation)
+        GameSessionAction::JoinGroup { group_id } => {
+            let group = group_mut(group_id);
+            let index = group
+                .invited
+                .iter()
+                .position(|user| *user == msg::source())
+                .expect("The user is not invited");
+            group.invited.remove(index);
+            group.members.push(msg::source());
+            msg::reply(GameSessionEvent::JoinedGroup { group_id }, 0)
+                .expect("Failed to send a reply");
+        }
+        // Handle the LeaveGroup action
+        GameSessionAction::LeaveGroup { group_id } => {
+            let group = group_mut(group_id);
+            let index = group
+                .members
+                .iter()
+                .position(|user| *user == msg::source())
+                .expect("The user is not a member");
+            group.members.remove(index);
+            // The longest-standing member takes over, and without members the group is deleted
+            match group.members.first() {
+                Some(member) if group.owner == msg::source() => group.owner = *member,
+                Some(_) => {}
+                None => *group = Group::default(),
+            }
+            msg::reply(GameSessionEvent::LeftGroup { group_id }, 0)
+                .expect("Failed to send a reply");
+        }
+        // Handle the WithdrawFees action (owner collects the hint fees)
+        GameSessionAction::WithdrawFees => {
+            let game_session = game_session_mut();
+            assert_eq!(msg::source(), game_session.owner, "Only the owner can withdraw fees");
+            let amount = game_session.fees;
+            game_session.fees = 0;
+            msg::reply(GameSessionEvent::FeesWithdrawn { amount }, amount)
+                .expect("Failed to send a reply");
         }
     }
 }
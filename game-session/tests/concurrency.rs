@@ -0,0 +1,211 @@
+mod utils;
+
+use game_session_io::*;
+use gtest::{Log, WasmProgram};
+use utils::*;
+use wordle_io::DEFAULT_BANK;
+
+// Wordle stand-in that never replies, keeping the session locked
+#[derive(Debug)]
+struct SilentWordle;
+
+impl WasmProgram for SilentWordle {
+    fn init(&mut self, _payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        Ok(None)
+    }
+
+    fn handle(&mut self, _payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        Ok(None)
+    }
+
+    fn handle_reply(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn handle_signal(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn state(&mut self) -> Result<Vec<u8>, &'static str> {
+        Ok(Vec::new())
+    }
+}
+
+// Wordle stand-in that fails every request
+#[derive(Debug)]
+struct FailingWordle;
+
+impl WasmProgram for FailingWordle {
+    fn init(&mut self, _payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        Ok(None)
+    }
+
+    fn handle(&mut self, _payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        Err("Wordle is broken")
+    }
+
+    fn handle_reply(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn handle_signal(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn state(&mut self) -> Result<Vec<u8>, &'static str> {
+        Ok(Vec::new())
+    }
+}
+
+fn config(busy_policy: BusyPolicy) -> GameSessionConfig {
+    GameSessionConfig {
+        busy_policy,
+        ..Default::default()
+    }
+}
+
+fn busy_log() -> Log {
+    Log::builder()
+        .dest(USER)
+        .source(GAME_SESSION_PROGRAM_ID)
+        .payload(GameSessionEvent::SessionBusy)
+}
+
+#[test]
+fn test_reject_while_waiting_for_wordle() {
+    let fixture = Fixture::with_wordle_mock(config(BusyPolicy::Reject), SilentWordle);
+
+    // The first request holds the lock while Wordle stays silent
+    let result = fixture.start(USER);
+    assert!(!result.main_failed());
+    let session_info = fixture.session(USER);
+    assert_eq!(session_info.lock, Some(result.sent_message_id()));
+    assert_eq!(
+        session_info.session_status,
+        SessionStatus::WaitWordleStartReply
+    );
+
+    // Later requests from the same player are rejected without touching the session
+    fixture.system.spend_blocks(1);
+    let result = fixture.start(USER);
+    assert!(!result.main_failed() && result.contains(&busy_log()));
+    fixture.system.spend_blocks(1);
+    let result = fixture.guess(USER, SECRET);
+    assert!(!result.main_failed() && result.contains(&busy_log()));
+
+    let session_info = fixture.session(USER);
+    assert_eq!(
+        session_info.session_status,
+        SessionStatus::WaitWordleStartReply
+    );
+    assert!(session_info.queue.is_empty());
+
+    // Other players are not affected by the lock
+    let result = fixture.start(OTHER_USER);
+    assert!(!result.main_failed() && !result.contains(&busy_log()));
+}
+
+#[test]
+fn test_queue_while_waiting_for_wordle() {
+    let fixture = Fixture::with_wordle_mock(config(BusyPolicy::Queue { limit: 1 }), SilentWordle);
+
+    let result = fixture.start(USER);
+    assert!(!result.main_failed());
+    let lock = result.sent_message_id();
+
+    // The second request waits in the queue
+    fixture.system.spend_blocks(1);
+    let result = fixture.send(USER, GameSessionAction::GiveUp);
+    assert!(!result.main_failed());
+    let queued = result.sent_message_id();
+    let session_info = fixture.session(USER);
+    assert_eq!(session_info.lock, Some(lock));
+    assert_eq!(session_info.queue, vec![queued]);
+
+    // The queue is full, so the third one is rejected
+    fixture.system.spend_blocks(1);
+    let result = fixture.start(USER);
+    assert!(!result.main_failed() && result.contains(&busy_log()));
+
+    // Once the Wordle request times out the lock is released and the queue drained
+    let results = fixture.system.spend_blocks(150);
+    let session_info = fixture.session(USER);
+    assert_eq!(session_info.lock, None);
+    assert!(session_info.queue.is_empty());
+    assert_eq!(session_info.session_status, SessionStatus::Init);
+
+    // The start failed, then the queued request was handled with a reply of its own
+    let replied = |event: GameSessionEvent| {
+        let log = Log::builder()
+            .dest(USER)
+            .source(GAME_SESSION_PROGRAM_ID)
+            .payload(event);
+        results.iter().any(|result| result.contains(&log))
+    };
+    assert!(replied(GameSessionEvent::RequestFailed));
+    assert!(replied(GameSessionEvent::NoGame));
+}
+
+#[test]
+fn test_failed_wordle_request_rolls_back() {
+    let fixture = Fixture::with_wordle_mock(config(BusyPolicy::Reject), FailingWordle);
+
+    // WaitWordleStartReply -> Init
+    let result = fixture.start(USER);
+    assert_eq!(reply(&result, USER), GameSessionEvent::RequestFailed);
+    let session_info = fixture.session(USER);
+    assert_eq!(session_info.session_status, SessionStatus::Init);
+    assert_eq!(session_info.lock, None);
+
+    // The player can try again right away
+    let result = fixture.start(USER);
+    assert_eq!(reply(&result, USER), GameSessionEvent::RequestFailed);
+}
+
+// Starts a game with gas enough to send the request to Wordle but not to handle its reply, so
+// the request fails after its lock on the session was saved
+fn start_out_of_gas(config: GameSessionConfig) -> Fixture {
+    let start = GameSessionAction::StartGame {
+        bank: DEFAULT_BANK.to_string(),
+        mode: GameMode::Ranked,
+    };
+    // With `low` gas the request fails before waiting for Wordle, with `high` it succeeds
+    let (mut low, mut high) = (0, 10 * config.reservation_amount);
+    while high - low > 1 {
+        let gas_limit = low + (high - low) / 2;
+        let fixture = Fixture::with_config(config);
+        fixture.send_with_gas(USER, start.clone(), gas_limit);
+        let session_info = fixture.session(USER);
+        if session_info.lock.is_some() {
+            return fixture;
+        }
+        if session_info.session_status == SessionStatus::WaitUserInput {
+            high = gas_limit;
+        } else {
+            low = gas_limit;
+        }
+    }
+    panic!("No gas limit fails the request after the Wordle reply");
+}
+
+#[test]
+fn test_lock_of_failed_request_expires() {
+    let config = config(BusyPolicy::Reject);
+    let fixture = start_out_of_gas(config);
+    assert_eq!(fixture.status(USER), SessionStatus::WaitWordleStartReply);
+
+    // The lock of the failed request holds until it expires
+    fixture.system.spend_blocks(1);
+    let result = fixture.start(USER);
+    assert!(!result.main_failed() && result.contains(&busy_log()));
+
+    // Then the next request takes the session over and the failed start is rolled back
+    fixture.system.spend_blocks(config.lock_timeout);
+    assert!(matches!(
+        reply(&fixture.start(USER), USER),
+        GameSessionEvent::StartSuccess { .. }
+    ));
+    let session_info = fixture.session(USER);
+    assert_eq!(session_info.lock, None);
+    assert_eq!(session_info.session_status, SessionStatus::WaitUserInput);
+}
@@ -0,0 +1,58 @@
+mod utils;
+
+use game_session_io::*;
+use utils::*;
+
+#[test]
+fn test_give_up() {
+    let fixture = Fixture::new();
+    assert_eq!(
+        reply(&fixture.send(USER, GameSessionAction::GiveUp), USER),
+        GameSessionEvent::NoGame
+    );
+
+    fixture.start(USER);
+    fixture.guess(USER, "house");
+    let result = fixture.send(USER, GameSessionAction::GiveUp);
+    assert_eq!(
+        reply(&result, USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word: Some(SECRET.to_string())
+        }
+    );
+    let session = fixture.session(USER);
+    assert!(result.contains(&emitted_log(LifecycleEvent::GaveUp {
+        user: USER.into(),
+        game_id: session.session_id,
+        tries: 1,
+    })));
+    assert_eq!(
+        session.session_status,
+        SessionStatus::GameOver(GameStatus::Lose)
+    );
+    assert_eq!(
+        reply(&fixture.send(USER, GameSessionAction::GiveUp), USER),
+        GameSessionEvent::NoGame
+    );
+
+    // The timers of the given up game are ignored
+    let game_timeout = GameSessionConfig::default().game_timeout;
+    let results = fixture.system.spend_blocks(game_timeout + 1);
+    let log = game_over_log(USER);
+    assert!(!results.iter().any(|result| result.contains(&log)));
+
+    // The player can play again, and practice games can be given up too
+    fixture.start_practice(USER, None);
+    assert_eq!(
+        reply(&fixture.send(USER, GameSessionAction::GiveUp), USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word: Some(SECRET.to_string())
+        }
+    );
+    assert!(matches!(
+        reply(&fixture.start(USER), USER),
+        GameSessionEvent::StartSuccess { .. }
+    ));
+}
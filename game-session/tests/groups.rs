@@ -0,0 +1,145 @@
+mod utils;
+
+use game_session_io::*;
+use gstd::ActorId;
+use utils::*;
+
+#[test]
+fn test_groups() {
+    let fixture = Fixture::new();
+    let (user, other_user): (ActorId, ActorId) = (USER.into(), OTHER_USER.into());
+    let result = fixture.send(
+        USER,
+        GameSessionAction::CreateGroup {
+            name: "friends".to_string(),
+        },
+    );
+    assert_eq!(
+        reply(&result, USER),
+        GameSessionEvent::GroupCreated { group_id: 0 }
+    );
+    let join = || fixture.send(OTHER_USER, GameSessionAction::JoinGroup { group_id: 0 });
+
+    // Only invited players can join, and only members can invite
+    assert!(join().main_failed());
+    let invite = GameSessionAction::InviteToGroup {
+        group_id: 0,
+        user: other_user,
+    };
+    assert!(fixture.send(OTHER_USER, invite.clone()).main_failed());
+    fixture.send(USER, invite);
+    assert_eq!(
+        reply(&join(), OTHER_USER),
+        GameSessionEvent::JoinedGroup { group_id: 0 }
+    );
+    assert!(join().main_failed());
+    let group = &fixture.state().groups[0];
+    assert_eq!(group.members, [user, other_user]);
+    assert!(group.invited.is_empty());
+
+    // A hinted win ranks below a pure one; practice games do not count
+    fixture.start(OTHER_USER);
+    fixture.send(OTHER_USER, GameSessionAction::RequestHint);
+    fixture.guess(OTHER_USER, SECRET);
+    fixture.start(USER);
+    fixture.guess(USER, "house");
+    fixture.guess(USER, SECRET);
+    fixture.start_practice(USER, None);
+    fixture.guess(USER, SECRET);
+
+    let state = fixture.state();
+    let leaderboard = state.group_leaderboard(0);
+    assert_eq!(
+        leaderboard,
+        [
+            (
+                user,
+                PlayerStats {
+                    games: 1,
+                    wins: 1,
+                    pure_wins: 1,
+                    win_tries: 2,
+                }
+            ),
+            (
+                other_user,
+                PlayerStats {
+                    games: 1,
+                    wins: 1,
+                    pure_wins: 0,
+                    win_tries: 2,
+                }
+            ),
+        ]
+    );
+    let day = fixture.system.block_height() / BLOCKS_PER_DAY;
+    let results = state.group_daily_results(0, day);
+    assert_eq!(
+        results.iter().map(|result| result.user).collect::<Vec<_>>(),
+        [other_user, user]
+    );
+    assert!(state.group_daily_results(0, day + 1).is_empty());
+    assert!(state.group_leaderboard(1).is_empty());
+
+    let result = fixture.send(OTHER_USER, GameSessionAction::LeaveGroup { group_id: 0 });
+    assert_eq!(
+        reply(&result, OTHER_USER),
+        GameSessionEvent::LeftGroup { group_id: 0 }
+    );
+    assert_eq!(fixture.state().group_leaderboard(0).len(), 1);
+}
+
+#[test]
+fn test_group_ownership() {
+    let fixture = Fixture::new();
+    let (user, other_user): (ActorId, ActorId) = (USER.into(), OTHER_USER.into());
+    let create = |user: u64| {
+        fixture.send(
+            user,
+            GameSessionAction::CreateGroup {
+                name: "friends".to_string(),
+            },
+        )
+    };
+
+    // A player owns a limited number of groups
+    for group_id in 0..Group::MAX_OWNED as u32 {
+        assert_eq!(
+            reply(&create(USER), USER),
+            GameSessionEvent::GroupCreated { group_id }
+        );
+    }
+    assert!(create(USER).main_failed());
+
+    // The owner leaving hands the group over to the longest-standing member
+    fixture.send(
+        USER,
+        GameSessionAction::InviteToGroup {
+            group_id: 0,
+            user: other_user,
+        },
+    );
+    fixture.send(OTHER_USER, GameSessionAction::JoinGroup { group_id: 0 });
+    fixture.send(USER, GameSessionAction::LeaveGroup { group_id: 0 });
+    let group = &fixture.state().groups[0];
+    assert_eq!(group.owner, other_user);
+    assert_eq!(group.members, [other_user]);
+    assert_eq!(
+        reply(&create(USER), USER),
+        GameSessionEvent::GroupCreated {
+            group_id: Group::MAX_OWNED as u32
+        }
+    );
+
+    // The last member leaving deletes the group, and its id is reused
+    fixture.send(OTHER_USER, GameSessionAction::LeaveGroup { group_id: 0 });
+    assert_eq!(fixture.state().groups[0], Group::default());
+    assert_eq!(
+        reply(&create(OTHER_USER), OTHER_USER),
+        GameSessionEvent::GroupCreated { group_id: 0 }
+    );
+    assert_eq!(fixture.state().groups[0].owner, other_user);
+    assert!(fixture.state().groups[1..]
+        .iter()
+        .all(|group| group.owner == user));
+}
@@ -0,0 +1,131 @@
+mod utils;
+
+use game_session_io::*;
+use utils::*;
+
+#[test]
+fn test_hints() {
+    let fixture = Fixture::with_config(GameSessionConfig {
+        archive_after: 1,
+        ..Default::default()
+    });
+    let hint = |user: u64| reply(&fixture.send(user, GameSessionAction::RequestHint), user);
+    assert_eq!(hint(USER), GameSessionEvent::HintUnavailable);
+
+    // `house` finds every position of `horse` but the middle one
+    let game_id = fixture.start(USER).sent_message_id();
+    fixture.guess(USER, "house");
+    let result = fixture.send(USER, GameSessionAction::RequestHint);
+    assert_eq!(
+        reply(&result, USER),
+        GameSessionEvent::HintRevealed {
+            game_id,
+            position: 2,
+            letter: "r".to_string(),
+            tries_left: 3
+        }
+    );
+    assert!(result.contains(&emitted_log(LifecycleEvent::HintRevealed {
+        user: USER.into(),
+        game_id,
+        position: 2,
+        tries: 2,
+    })));
+    assert_eq!(hint(USER), GameSessionEvent::HintUnavailable);
+
+    // Hinted wins are not pure
+    fixture.guess(USER, SECRET);
+    fixture.system.spend_blocks(1);
+    fixture.send(USER, GameSessionAction::Cleanup);
+    let result = &fixture.state().results[0];
+    assert_eq!(
+        result.hints,
+        [Hint {
+            position: 2,
+            letter: "r".to_string()
+        }]
+    );
+    assert_eq!(result.status, GameStatus::Win);
+    assert!(!result.is_pure_win());
+
+    // The last try cannot be spent on a hint
+    fixture.start_practice(USER, Some(2));
+    assert!(matches!(
+        hint(USER),
+        GameSessionEvent::HintRevealed { tries_left: 1, .. }
+    ));
+    assert_eq!(hint(USER), GameSessionEvent::HintUnavailable);
+}
+
+#[test]
+fn test_hint_fee() {
+    const FEE: u128 = 10_000_000_000_000;
+    let config = GameSessionConfig {
+        hint_cost: HintCost::Fee(FEE),
+        ..Default::default()
+    };
+    let fixture = Fixture::with_config(config);
+    fixture.system.mint_to(USER, 10 * FEE);
+    let hint = |value: u128| {
+        let result =
+            fixture
+                .game_session()
+                .send_with_value(USER, GameSessionAction::RequestHint, value);
+        reply(&result, USER)
+    };
+    let balance = || fixture.system.balance_of(GAME_SESSION_PROGRAM_ID);
+    let initial_balance = balance();
+
+    // Without a game or with too little value, the whole value is returned
+    assert_eq!(hint(FEE), GameSessionEvent::HintUnavailable);
+    fixture.start(USER);
+    assert_eq!(hint(FEE / 2), GameSessionEvent::HintUnavailable);
+    assert_eq!(balance(), initial_balance);
+
+    // A paid hint costs no try, and the program keeps just the fee
+    assert!(matches!(
+        hint(2 * FEE),
+        GameSessionEvent::HintRevealed { tries_left: 5, .. }
+    ));
+    assert_eq!(fixture.session(USER).hints.len(), 1);
+    assert_eq!(balance(), initial_balance + FEE);
+    assert_eq!(fixture.state().fees, FEE);
+
+    // No hints once the game is over
+    fixture.guess(USER, SECRET);
+    assert_eq!(hint(FEE), GameSessionEvent::HintUnavailable);
+    assert_eq!(balance(), initial_balance + FEE);
+
+    // Only the owner can withdraw the fees
+    let withdraw = |user: u64| fixture.send(user, GameSessionAction::WithdrawFees);
+    assert!(withdraw(OTHER_USER).main_failed());
+    assert_eq!(
+        reply(&withdraw(USER), USER),
+        GameSessionEvent::FeesWithdrawn { amount: FEE }
+    );
+    assert_eq!(balance(), initial_balance);
+    assert_eq!(fixture.state().fees, 0);
+}
+
+#[test]
+fn test_hint_fee_refunded_on_failure() {
+    const FEE: u128 = 10_000_000_000_000;
+    let config = GameSessionConfig {
+        hint_cost: HintCost::Fee(FEE),
+        ..Default::default()
+    };
+    let fixture = Fixture::with_wordle_mock(config, StartOnlyWordle);
+    fixture.system.mint_to(USER, 10 * FEE);
+    let initial_balance = fixture.system.balance_of(GAME_SESSION_PROGRAM_ID);
+
+    fixture.start(USER);
+    let result = fixture
+        .game_session()
+        .send_with_value(USER, GameSessionAction::RequestHint, FEE);
+    assert_eq!(reply(&result, USER), GameSessionEvent::RequestFailed);
+    assert_eq!(
+        fixture.system.balance_of(GAME_SESSION_PROGRAM_ID),
+        initial_balance
+    );
+    assert_eq!(fixture.state().fees, 0);
+}
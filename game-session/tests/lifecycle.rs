@@ -0,0 +1,85 @@
+mod utils;
+
+use game_session_io::*;
+use gstd::prelude::Decode;
+use utils::*;
+use wordle_io::DEFAULT_BANK;
+
+#[test]
+fn test_lifecycle_events() {
+    let fixture = Fixture::new();
+    let user = USER.into();
+
+    let result = fixture.start(USER);
+    let GameSessionEvent::StartSuccess {
+        game_id,
+        deadline_block,
+        ..
+    } = reply(&result, USER)
+    else {
+        panic!("Unexpected reply to StartGame");
+    };
+    assert!(result.contains(&emitted_log(LifecycleEvent::GameStarted {
+        user,
+        game_id,
+        bank: DEFAULT_BANK.to_string(),
+        deadline_block,
+    })));
+
+    let result = fixture.guess(USER, "abcde");
+    assert!(
+        result.contains(&emitted_log(LifecycleEvent::GuessSubmitted {
+            user,
+            game_id,
+            word: "abcde".to_string(),
+        }))
+    );
+    assert!(
+        result.contains(&emitted_log(LifecycleEvent::GuessEvaluated {
+            user,
+            game_id,
+            tries: 1,
+            correct_positions: vec![4],
+            contained_in_word: vec![],
+        }))
+    );
+
+    let result = fixture.guess(USER, SECRET);
+    assert!(result.contains(&emitted_log(LifecycleEvent::GameWon {
+        user,
+        game_id,
+        tries: 2,
+    })));
+
+    // A guess Wordle rejects is submitted but never evaluated
+    let game_id = fixture.start(USER).sent_message_id();
+    let result = fixture.guess(USER, "abc");
+    assert!(
+        result.contains(&emitted_log(LifecycleEvent::GuessSubmitted {
+            user,
+            game_id,
+            word: "abc".to_string(),
+        }))
+    );
+    assert!(!result.log().iter().any(|log| matches!(
+        LifecycleEvent::decode(&mut log.payload()),
+        Ok(LifecycleEvent::GuessEvaluated { .. })
+    )));
+
+    for _ in 0..4 {
+        fixture.guess(USER, "house");
+    }
+    let result = fixture.guess(USER, "house");
+    assert!(result.contains(&emitted_log(LifecycleEvent::GameLost {
+        user,
+        game_id,
+        tries: 5,
+    })));
+
+    let config = GameSessionConfig {
+        tries_limit: 6,
+        ..Default::default()
+    };
+    let result = fixture.send(USER, GameSessionAction::UpdateConfig(config));
+    assert!(result.contains(&emitted_log(LifecycleEvent::ConfigChanged(config))));
+}
@@ -0,0 +1,120 @@
+mod utils;
+
+use game_session_io::*;
+use utils::*;
+
+#[test]
+fn test_practice() {
+    let fixture = Fixture::new();
+    let broadcasts = |result: &gtest::RunResult| {
+        result
+            .log()
+            .iter()
+            .filter(|log| log.destination() == 0u64.into())
+            .count()
+    };
+
+    let result = fixture.start_practice(USER, None);
+    assert!(matches!(
+        reply(&result, USER),
+        GameSessionEvent::StartSuccess {
+            tries_left: u8::MAX,
+            deadline_block: u32::MAX,
+            ..
+        }
+    ));
+    assert_eq!(broadcasts(&result), 0);
+
+    // Unlimited tries, no timers and no lifecycle events
+    for _ in 0..10 {
+        let result = fixture.guess(USER, "house");
+        assert!(matches!(
+            reply(&result, USER),
+            GameSessionEvent::CheckWordResult {
+                tries_left: u8::MAX,
+                ..
+            }
+        ));
+        assert_eq!(broadcasts(&result), 0);
+    }
+    let game_timeout = GameSessionConfig::default().game_timeout;
+    fixture.system.spend_blocks(game_timeout + 1);
+    assert_eq!(fixture.status(USER), SessionStatus::WaitUserInput);
+
+    assert_eq!(
+        reply(&fixture.send(USER, GameSessionAction::Reveal), USER),
+        GameSessionEvent::WordRevealed {
+            word: SECRET.to_string()
+        }
+    );
+    assert_eq!(
+        fixture.status(USER),
+        SessionStatus::GameOver(GameStatus::Lose)
+    );
+    assert_eq!(
+        reply(&fixture.send(USER, GameSessionAction::Reveal), USER),
+        GameSessionEvent::NotPractice
+    );
+
+    // Ranked games cannot be revealed
+    fixture.start(USER);
+    assert_eq!(
+        reply(&fixture.send(USER, GameSessionAction::Reveal), USER),
+        GameSessionEvent::NotPractice
+    );
+    fixture.guess(USER, SECRET);
+
+    // A practice game can have a limit of its own
+    assert!(fixture.start_practice(USER, Some(0)).main_failed());
+    fixture.start_practice(USER, Some(2));
+    fixture.guess(USER, "house");
+    assert_eq!(
+        reply(&fixture.guess(USER, "house"), USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word: Some(SECRET.to_string())
+        }
+    );
+}
+
+#[test]
+fn test_reveal_without_wordle_reply() {
+    let fixture = Fixture::with_wordle_mock(GameSessionConfig::default(), StartOnlyWordle);
+    fixture.start_practice(USER, None);
+
+    // The practice game is over even though Wordle did not reveal its word
+    assert_eq!(
+        reply(&fixture.send(USER, GameSessionAction::Reveal), USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word: None
+        }
+    );
+    let session_info = fixture.session(USER);
+    assert_eq!(
+        session_info.session_status,
+        SessionStatus::GameOver(GameStatus::Lose)
+    );
+    assert_eq!(session_info.lock, None);
+}
+
+#[test]
+fn test_practice_beyond_u8_tries() {
+    let fixture = Fixture::new();
+    fixture.start_practice(USER, None);
+
+    // Wordle does not end an unlimited game once the tries no longer fit in a `u8`
+    for _ in 0..300 {
+        assert!(matches!(
+            reply(&fixture.guess(USER, "house"), USER),
+            GameSessionEvent::CheckWordResult { .. }
+        ));
+    }
+    assert_eq!(
+        reply(&fixture.guess(USER, SECRET), USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Win,
+            word: None
+        }
+    );
+}
@@ -0,0 +1,35 @@
+mod utils;
+
+use game_session_io::*;
+use utils::*;
+
+#[test]
+fn test_share_grid() {
+    let fixture = Fixture::new();
+    fixture.start(USER);
+    fixture.guess(USER, "house");
+    assert_eq!(fixture.state().share_grid(USER.into()), None);
+
+    fixture.guess(USER, SECRET);
+    assert_eq!(
+        fixture.state().share_grid(USER.into()).unwrap(),
+        "Wordle day 0 2/5\n\n🟩🟩⬛🟩🟩\n🟩🟩🟩🟩🟩"
+    );
+
+    // Lost and hinted games, dated by the day they ended on when they cross into the next
+    let block_height = fixture.system.block_height();
+    fixture
+        .system
+        .spend_blocks(BLOCKS_PER_DAY - block_height - 10);
+    fixture.start(USER);
+    fixture.send(USER, GameSessionAction::RequestHint);
+    fixture.system.spend_blocks(20);
+    for _ in 0..4 {
+        fixture.guess(USER, "shore");
+    }
+    assert_eq!(
+        fixture.state().share_grid(USER.into()).unwrap(),
+        "Wordle day 1 X/5 (1 hint)\n\n🟨🟨🟨🟨🟩\n🟨🟨🟨🟨🟩\n🟨🟨🟨🟨🟩\n🟨🟨🟨🟨🟩"
+    );
+    assert_eq!(fixture.state().share_grid(OTHER_USER.into()), None);
+}
@@ -0,0 +1,101 @@
+use game_session_io::*;
+
+fn all_statuses() -> Vec<SessionStatus> {
+    vec![
+        SessionStatus::Init,
+        SessionStatus::WaitWordleStartReply,
+        SessionStatus::WaitUserInput,
+        SessionStatus::WaitWordleCheckWordReply,
+        SessionStatus::GameOver(GameStatus::Win),
+        SessionStatus::GameOver(GameStatus::Lose),
+    ]
+}
+
+fn all_transitions() -> Vec<Transition> {
+    vec![
+        Transition::Start,
+        Transition::Started,
+        Transition::Submit,
+        Transition::Checked,
+        Transition::Finish(GameStatus::Win),
+        Transition::Finish(GameStatus::Lose),
+        Transition::Cancel,
+    ]
+}
+
+// Mirrors the transition table documented on `SessionStatus`
+fn expected(status: &SessionStatus, transition: &Transition) -> Option<SessionStatus> {
+    match (status, transition) {
+        (SessionStatus::Init, Transition::Start) => Some(SessionStatus::WaitWordleStartReply),
+        (SessionStatus::GameOver(_), Transition::Start) => {
+            Some(SessionStatus::WaitWordleStartReply)
+        }
+        (SessionStatus::WaitWordleStartReply, Transition::Started) => {
+            Some(SessionStatus::WaitUserInput)
+        }
+        (SessionStatus::WaitWordleStartReply, Transition::Cancel) => Some(SessionStatus::Init),
+        (SessionStatus::WaitUserInput, Transition::Submit) => {
+            Some(SessionStatus::WaitWordleCheckWordReply)
+        }
+        (SessionStatus::WaitUserInput, Transition::Finish(status)) => {
+            Some(SessionStatus::GameOver(status.clone()))
+        }
+        (SessionStatus::WaitWordleCheckWordReply, Transition::Checked) => {
+            Some(SessionStatus::WaitUserInput)
+        }
+        (SessionStatus::WaitWordleCheckWordReply, Transition::Cancel) => {
+            Some(SessionStatus::WaitUserInput)
+        }
+        (SessionStatus::WaitWordleCheckWordReply, Transition::Finish(status)) => {
+            Some(SessionStatus::GameOver(status.clone()))
+        }
+        _ => None,
+    }
+}
+
+#[test]
+fn test_transition_table() {
+    for status in all_statuses() {
+        for transition in all_transitions() {
+            assert_eq!(
+                status.next(&transition),
+                expected(&status, &transition),
+                "{:?} on {:?}",
+                status,
+                transition
+            );
+        }
+    }
+}
+
+#[test]
+fn test_every_illegal_transition_panics() {
+    for status in all_statuses() {
+        for transition in all_transitions() {
+            if expected(&status, &transition).is_some() {
+                continue;
+            }
+            let mut session_info = SessionInfo {
+                session_status: status.clone(),
+                ..Default::default()
+            };
+            let result = std::panic::catch_unwind(move || session_info.apply(transition));
+            assert!(
+                result.is_err(),
+                "{:?} accepted an illegal transition",
+                status
+            );
+        }
+    }
+}
+
+#[test]
+fn test_start_while_waiting_for_wordle_is_rejected() {
+    let mut session_info = SessionInfo::default();
+    session_info.apply(Transition::Start);
+    assert_eq!(
+        session_info.session_status,
+        SessionStatus::WaitWordleStartReply
+    );
+    assert_eq!(session_info.session_status.next(&Transition::Start), None);
+}
@@ -0,0 +1,141 @@
+mod utils;
+
+use game_session_io::*;
+use utils::*;
+
+#[test]
+fn test_active_sessions_limit() {
+    let fixture = Fixture::with_config(GameSessionConfig {
+        max_active_sessions: Some(1),
+        ..Default::default()
+    });
+    fixture.start(USER);
+    assert_eq!(
+        reply(&fixture.start(OTHER_USER), OTHER_USER),
+        GameSessionEvent::TooManySessions
+    );
+    assert_eq!(fixture.status(OTHER_USER), SessionStatus::Init);
+
+    // A finished game frees its place
+    fixture.guess(USER, SECRET);
+    assert!(matches!(
+        reply(&fixture.start(OTHER_USER), OTHER_USER),
+        GameSessionEvent::StartSuccess { .. }
+    ));
+}
+
+#[test]
+fn test_cleanup() {
+    let fixture = Fixture::with_config(GameSessionConfig {
+        archive_after: 10,
+        max_results: 1,
+        ..Default::default()
+    });
+    let cleanup = |user: u64| fixture.send(user, GameSessionAction::Cleanup);
+    let game_id = fixture.start(USER).sent_message_id();
+    fixture.guess(USER, SECRET);
+    fixture.start(OTHER_USER);
+
+    // Only the owner can clean up, and recently finished sessions are kept
+    assert!(cleanup(OTHER_USER).main_failed());
+    assert_eq!(
+        reply(&cleanup(USER), USER),
+        GameSessionEvent::CleanedUp {
+            archived: 0,
+            pruned: 0
+        }
+    );
+    assert_eq!(fixture.state().game_sessions.len(), 2);
+
+    fixture.system.spend_blocks(10);
+    assert_eq!(
+        reply(&cleanup(USER), USER),
+        GameSessionEvent::CleanedUp {
+            archived: 1,
+            pruned: 0
+        }
+    );
+    let state = fixture.state();
+    assert_eq!(state.game_sessions.len(), 1);
+    assert_eq!(state.game_sessions[0].0, OTHER_USER.into());
+    assert_eq!(state.results.len(), 1);
+    let result = &state.results[0];
+    assert_eq!(result.user, USER.into());
+    assert_eq!(result.game_id, game_id);
+    assert_eq!(result.status, GameStatus::Win);
+    assert_eq!(result.tries, 1);
+    assert!(result.is_pure_win());
+
+    // Beyond `max_results` the oldest results are pruned
+    for _ in 0..5 {
+        fixture.guess(OTHER_USER, "house");
+    }
+    fixture.system.spend_blocks(10);
+    assert_eq!(
+        reply(&cleanup(USER), USER),
+        GameSessionEvent::CleanedUp {
+            archived: 1,
+            pruned: 1
+        }
+    );
+    let state = fixture.state();
+    assert!(state.game_sessions.is_empty());
+    assert_eq!(state.results[0].user, OTHER_USER.into());
+    assert_eq!(state.results[0].status, GameStatus::Lose);
+
+    // A player can play again after being archived
+    assert!(matches!(
+        reply(&fixture.start(USER), USER),
+        GameSessionEvent::StartSuccess { .. }
+    ));
+}
+
+#[test]
+fn test_scheduled_cleanup() {
+    let fixture = Fixture::with_config(GameSessionConfig {
+        archive_after: 0,
+        cleanup_interval: Some(5),
+        ..Default::default()
+    });
+    fixture.start(USER);
+    fixture.guess(USER, SECRET);
+
+    // Without pooled reservations the program does not schedule cleanups
+    fixture.system.spend_blocks(6);
+    assert_eq!(fixture.state().game_sessions.len(), 1);
+
+    // A top-up gives the program the gas to clean up by itself from then on
+    fixture.send(USER, GameSessionAction::TopUpReservations { count: 2 });
+    fixture.system.spend_blocks(6);
+    let state = fixture.state();
+    assert!(state.game_sessions.is_empty());
+    assert_eq!(state.results.len(), 1);
+}
+
+#[test]
+fn test_failed_scheduled_cleanup() {
+    let config = GameSessionConfig {
+        archive_after: 0,
+        cleanup_interval: Some(5),
+        ..Default::default()
+    };
+    let fixture = Fixture::with_config(GameSessionConfig {
+        reservation_amount: 100_000_000,
+        ..config
+    });
+    fixture.start(USER);
+    fixture.guess(USER, SECRET);
+
+    // The reservation is too small for the scheduled cleanup, which fails
+    fixture.send(USER, GameSessionAction::TopUpReservations { count: 1 });
+    fixture.system.spend_blocks(6);
+    assert_eq!(fixture.state().game_sessions.len(), 1);
+
+    // Once its block has passed, the failed cleanup no longer holds the next one
+    fixture.send(USER, GameSessionAction::UpdateConfig(config));
+    fixture.send(USER, GameSessionAction::TopUpReservations { count: 1 });
+    fixture.system.spend_blocks(6);
+    let state = fixture.state();
+    assert!(state.game_sessions.is_empty());
+    assert_eq!(state.results.len(), 1);
+}
@@ -1,125 +1,791 @@
+mod utils;
+
 use game_session_io::*;
-use gtest::{Log, ProgramBuilder, System};
+use gstd::prelude::{Decode, Encode};
+use gtest::Log;
+use utils::*;
+use wordle_io::{
+    merkle_proof, merkle_root, pack_words, Action, Alphabet, BankInit, Event, PlayedWords,
+    WordList, WordleInit, WordleState,
+};
+
+#[test]
+fn test_win() {
+    let fixture = Fixture::new();
+
+    let result = fixture.start(USER);
+    let GameSessionEvent::StartSuccess {
+        game_id,
+        tries_left,
+        deadline_block,
+    } = reply(&result, USER)
+    else {
+        panic!("Unexpected reply to StartGame");
+    };
+    assert_eq!(game_id, result.sent_message_id());
+    assert_eq!(tries_left, 5);
+    assert!(deadline_block > fixture.system.block_height());
 
-const GAME_SESSION_PROGRAM_ID: u64 = 1;
-const WORDLE_PROGRAM_ID: u64 = 2;
+    let result = fixture.guess(USER, "abcde");
+    assert_eq!(
+        reply(&result, USER),
+        GameSessionEvent::CheckWordResult {
+            game_id,
+            correct_positions: vec![4],
+            contained_in_word: vec![],
+            tries_left: 4,
+            deadline_block,
+        }
+    );
 
-const USER: u64 = 3;
+    let result = fixture.guess(USER, SECRET);
+    assert_eq!(
+        reply(&result, USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Win,
+            word: None
+        }
+    );
+
+    let session_info = fixture.session(USER);
+    assert_eq!(
+        session_info.session_status,
+        SessionStatus::GameOver(GameStatus::Win)
+    );
+    assert_eq!(session_info.tries, 2);
+}
 
 #[test]
-fn test_win() {
-let system = System::new();
-    system.init_logger();
-
-    let game_session_program =
-        ProgramBuilder::from_file("../target/wasm32-unknown-unknown/debug/game_session.opt.wasm")
-            .with_id(GAME_SESSION_PROGRAM_ID)
-            .build(&system);
-    let wordle_program =
-        ProgramBuilder::from_file("../target/wasm32-unknown-unknown/debug/wordle.opt.wasm")
-            .with_id(WORDLE_PROGRAM_ID)
-            .build(&system);
-
-    let result = wordle_program.send_bytes(USER, []);
-    assert!(!result.main_failed());
-    game_session_program.send(USER,GameSessionInit {wordle_program_id: WORDLE_PROGRAM_ID.into(),},);
+fn test_lose_exceeded_tries_limit() {
+    let fixture = Fixture::new();
+    fixture.start(USER);
+
+    for tries_left in (1..5).rev() {
+        let result = fixture.guess(USER, "house");
+        let GameSessionEvent::CheckWordResult {
+            correct_positions,
+            contained_in_word,
+            tries_left: reported,
+            ..
+        } = reply(&result, USER)
+        else {
+            panic!("Unexpected reply to CheckWord");
+        };
+        assert_eq!(correct_positions, vec![0, 1, 3, 4]);
+        assert!(contained_in_word.is_empty());
+        assert_eq!(reported, tries_left);
+    }
+
+    let result = fixture.guess(USER, "house");
+    assert_eq!(
+        reply(&result, USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word: Some(SECRET.to_string())
+        }
+    );
+    assert_eq!(
+        fixture.status(USER),
+        SessionStatus::GameOver(GameStatus::Lose)
+    );
+    assert_eq!(fixture.session(USER).tries, 5);
+}
+
+#[test]
+fn test_lose_timeout() {
+    let fixture = Fixture::new();
 
-    game_session_program.send(USER, GameSessionAction::StartGame);
+    let result = fixture.start(USER);
+    let GameSessionEvent::StartSuccess { deadline_block, .. } = reply(&result, USER) else {
+        panic!("Unexpected reply to StartGame");
+    };
 
-    game_session_program.send(USER,GameSessionAction::CheckWord {word: "abcde".to_string(),},);
+    // Just before the deadline the game is still running
+    let blocks = deadline_block - fixture.system.block_height() - 2;
+    let results = fixture.system.spend_blocks(blocks);
+    let log = game_over_log(USER);
+    assert!(!results.iter().any(|result| result.contains(&log)));
+    assert_eq!(fixture.status(USER), SessionStatus::WaitUserInput);
 
-    game_session_program.send(USER,GameSessionAction::CheckWord {word: "horse".to_string(),},);
+    let results = fixture.system.spend_blocks(3);
+    assert!(results.iter().any(|result| result.contains(&log)));
+    let log = emitted_log(LifecycleEvent::TimedOut {
+        user: USER.into(),
+        game_id: fixture.session(USER).session_id,
+        move_number: None,
+    });
+    assert!(results.iter().any(|result| result.contains(&log)));
+    assert_eq!(
+        fixture.status(USER),
+        SessionStatus::GameOver(GameStatus::Lose)
+    );
+}
 
-    let state: GameSessionState = game_session_program.read_state(()).unwrap();
-    println!("{:?}", state);
+#[test]
+fn test_timeout_after_game_over_is_ignored() {
+    let fixture = Fixture::new();
+    fixture.start(USER);
+    fixture.guess(USER, SECRET);
 
+    let game_timeout = GameSessionConfig::default().game_timeout;
+    let results = fixture.system.spend_blocks(game_timeout + 1);
+    let log = game_over_log(USER);
+    assert!(!results.iter().any(|result| result.contains(&log)));
     assert_eq!(
-        state.game_sessions[0].1.session_status,
+        fixture.status(USER),
         SessionStatus::GameOver(GameStatus::Win)
     );
-    assert_eq!(state.game_sessions[0].1.tries, 2);
 }
 
 #[test]
-fn test_lose_exceeded_tries_limit() {
-    let system = System::new();
-    system.init_logger();
-
-    let game_session_program =
-        ProgramBuilder::from_file("../target/wasm32-unknown-unknown/debug/game_session.opt.wasm")
-            .with_id(GAME_SESSION_PROGRAM_ID)
-            .build(&system);
-    let wordle_program =
-        ProgramBuilder::from_file("../target/wasm32-unknown-unknown/debug/wordle.opt.wasm")
-            .with_id(WORDLE_PROGRAM_ID)
-            .build(&system);
-
-    let result = wordle_program.send_bytes(USER, []);
-    assert!(!result.main_failed());
+fn test_timeout_of_previous_game_is_ignored() {
+    let fixture = Fixture::new();
+    let game_timeout = GameSessionConfig::default().game_timeout;
 
-    let result = game_session_program.send(
-        USER,
-        GameSessionInit {
-            wordle_program_id: WORDLE_PROGRAM_ID.into(),
-        },
+    fixture.start(USER);
+    fixture.guess(USER, SECRET);
+    fixture.system.spend_blocks(game_timeout / 2);
+    fixture.start(USER);
+
+    // The first game's timer fires while the second game is running
+    let results = fixture.system.spend_blocks(game_timeout / 2 + 2);
+    let log = game_over_log(USER);
+    assert!(!results.iter().any(|result| result.contains(&log)));
+    assert_eq!(fixture.status(USER), SessionStatus::WaitUserInput);
+
+    let results = fixture.system.spend_blocks(game_timeout / 2);
+    assert!(results.iter().any(|result| result.contains(&log)));
+    assert_eq!(
+        fixture.status(USER),
+        SessionStatus::GameOver(GameStatus::Lose)
     );
-    assert!(!result.main_failed());
+}
 
-    // StartGame success
-    game_session_program.send(USER, GameSessionAction::StartGame);
+#[test]
+fn test_session_status_transitions() {
+    let fixture = Fixture::with_config(GameSessionConfig {
+        tries_limit: 2,
+        ..Default::default()
+    });
+    assert_eq!(fixture.status(USER), SessionStatus::Init);
+
+    // Init -> WaitWordleStartReply -> WaitUserInput
+    fixture.start(USER);
+    assert_eq!(fixture.status(USER), SessionStatus::WaitUserInput);
+
+    // WaitUserInput -> WaitWordleCheckWordReply -> WaitUserInput
+    fixture.guess(USER, "house");
+    assert_eq!(fixture.status(USER), SessionStatus::WaitUserInput);
+
+    // WaitUserInput -> WaitWordleCheckWordReply -> GameOver(Lose)
+    fixture.guess(USER, "house");
+    assert_eq!(
+        fixture.status(USER),
+        SessionStatus::GameOver(GameStatus::Lose)
+    );
 
-    game_session_program.send(USER,GameSessionAction::CheckWord {word: "house".to_string(),},);
-    game_session_program.send(USER,GameSessionAction::CheckWord {word: "house".to_string(),},);
-    game_session_program.send(USER,GameSessionAction::CheckWord {word: "house".to_string(),},);
-    game_session_program.send(USER,GameSessionAction::CheckWord {word: "house".to_string(),},);
-    game_session_program.send(USER,GameSessionAction::CheckWord {word: "house".to_string(),},);
+    // GameOver -> WaitWordleStartReply -> WaitUserInput
+    fixture.start(USER);
+    assert_eq!(fixture.status(USER), SessionStatus::WaitUserInput);
 
-    let state: GameSessionState = game_session_program.read_state(b"").unwrap();
-    println!("{:?}", state);
-     assert_eq!(
-            state.game_sessions[0].1.session_status,
-            SessionStatus::GameOver(GameStatus::Lose)
+    // WaitUserInput -> WaitWordleCheckWordReply -> GameOver(Win)
+    fixture.guess(USER, SECRET);
+    assert_eq!(
+        fixture.status(USER),
+        SessionStatus::GameOver(GameStatus::Win)
+    );
+}
+
+#[test]
+fn test_restart_after_game_over() {
+    let fixture = Fixture::new();
+
+    let first = fixture.start(USER).sent_message_id();
+    fixture.guess(USER, "house");
+    fixture.guess(USER, SECRET);
+    assert_eq!(
+        fixture.status(USER),
+        SessionStatus::GameOver(GameStatus::Win)
+    );
+
+    let result = fixture.start(USER);
+    assert!(matches!(
+        reply(&result, USER),
+        GameSessionEvent::StartSuccess { tries_left: 5, .. }
+    ));
+    let session_info = fixture.session(USER);
+    assert_eq!(session_info.session_status, SessionStatus::WaitUserInput);
+    assert_eq!(session_info.tries, 0);
+    assert_eq!(session_info.session_id, result.sent_message_id());
+    assert_ne!(session_info.session_id, first);
+}
+
+#[test]
+fn test_invalid_words() {
+    let fixture = Fixture::new();
+    fixture.start(USER);
+
+    for word in [
+        "",
+        "hors",
+        "horses",
+        "Horse",
+        "hors3",
+        "ñandú",
+        "hor e",
+        "пісня",
+    ] {
+        let result = fixture.guess(USER, word);
+        assert_eq!(
+            reply(&result, USER),
+            GameSessionEvent::InvalidWord,
+            "{:?} was accepted",
+            word
         );
+    }
+
+    // Rejected words do not use a try
+    let session_info = fixture.session(USER);
+    assert_eq!(session_info.session_status, SessionStatus::WaitUserInput);
+    assert_eq!(session_info.tries, 0);
+    assert!(matches!(
+        reply(&fixture.guess(USER, "house"), USER),
+        GameSessionEvent::CheckWordResult { tries_left: 4, .. }
+    ));
 }
 
 #[test]
-fn test_lose_timeout() {
-    let system = System::new();
-    system.init_logger();
-
-    let game_session_program =
-        ProgramBuilder::from_file("../target/wasm32-unknown-unknown/debug/game_session.opt.wasm")
-            .with_id(GAME_SESSION_PROGRAM_ID)
-            .build(&system);
-    let wordle_program =
-        ProgramBuilder::from_file("../target/wasm32-unknown-unknown/debug/wordle.opt.wasm")
-            .with_id(WORDLE_PROGRAM_ID)
-            .build(&system);
-
-    let result = wordle_program.send_bytes(USER, []);
-    assert!(!result.main_failed());
-    let result = game_session_program.send(
-        USER,
-        GameSessionInit {
-            wordle_program_id: WORDLE_PROGRAM_ID.into(),
+fn test_multiple_banks() {
+    let fixture = Fixture::with_wordle_init(
+        Default::default(),
+        WordleInit {
+            banks: vec![
+                BankInit {
+                    id: "en".to_string(),
+                    alphabet: Alphabet::English,
+                    words: vec![SECRET.to_string()],
+                },
+                BankInit {
+                    id: "es".to_string(),
+                    alphabet: Alphabet::Spanish,
+                    words: vec!["ñandú".to_string()],
+                },
+            ],
         },
     );
-    assert!(!result.main_failed());
+    fixture.start_with_bank(USER, "es");
+    fixture.start_with_bank(OTHER_USER, "en");
+    assert_eq!(fixture.session(USER).bank, "es");
+    assert_eq!(fixture.session(OTHER_USER).bank, "en");
+
+    // `ñ` is a letter of its own, while accents are dropped
+    let session_info = fixture.session(USER);
+    assert_eq!(
+        reply(&fixture.guess(USER, "nandu"), USER),
+        GameSessionEvent::CheckWordResult {
+            game_id: session_info.session_id,
+            correct_positions: vec![1, 2, 3, 4],
+            contained_in_word: vec![],
+            tries_left: 4,
+            deadline_block: session_info.deadline_block,
+        }
+    );
+    assert_eq!(
+        reply(&fixture.guess(USER, "ñandu"), USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Win,
+            word: None
+        }
+    );
+
+    // Guesses are checked against the alphabet of the player's bank
+    assert_eq!(
+        reply(&fixture.guess(OTHER_USER, "ñandú"), OTHER_USER),
+        GameSessionEvent::InvalidWord
+    );
+    assert_eq!(
+        reply(&fixture.guess(OTHER_USER, SECRET), OTHER_USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Win,
+            word: None
+        }
+    );
+
+    // An unknown bank rolls the session back
+    assert_eq!(
+        reply(&fixture.start_with_bank(USER, "uk"), USER),
+        GameSessionEvent::UnknownBank
+    );
+    assert_eq!(fixture.status(USER), SessionStatus::Init);
+}
+
+#[test]
+fn test_upload_words() {
+    let fixture = Fixture::with_wordle_init(
+        Default::default(),
+        WordleInit {
+            banks: vec![
+                BankInit {
+                    id: "en".to_string(),
+                    words: vec![SECRET.to_string()],
+                    guesses: vec!["house".to_string()],
+                    ..Default::default()
+                },
+                BankInit {
+                    id: "themed:space".to_string(),
+                    ..Default::default()
+                },
+            ],
+        },
+    );
+    let wordle_program = fixture.system.get_program(WORDLE_PROGRAM_ID).unwrap();
+    let upload = |user: u64, bank: &str, list: WordList, words: &[&str]| {
+        let words: Vec<_> = words
+            .iter()
+            .map(|word| wordle_core::validate_word(word).unwrap())
+            .collect();
+        let packed = pack_words(wordle_core::Alphabet::English, &words).unwrap();
+        wordle_program.send(
+            user,
+            Action::UploadWords {
+                bank: bank.to_string(),
+                list,
+                packed,
+            },
+        )
+    };
+
+    // Only words of the dictionary or the answers are accepted as guesses
+    fixture.start(USER);
+    assert_eq!(
+        reply(&fixture.guess(USER, "abcde"), USER),
+        GameSessionEvent::InvalidWord
+    );
+    assert!(matches!(
+        reply(&fixture.guess(USER, "house"), USER),
+        GameSessionEvent::CheckWordResult { tries_left: 4, .. }
+    ));
+
+    // Only the owner can upload words
+    assert!(upload(OTHER_USER, "en", WordList::Guesses, &["abcde"]).main_failed());
+    let result = upload(USER, "en", WordList::Guesses, &["abcde", "house"]);
+    let log = Log::builder().dest(USER).payload(Event::WordsUploaded {
+        bank: "en".to_string(),
+        answers: 1,
+        guesses: 2,
+    });
+    assert!(result.contains(&log));
+    assert!(matches!(
+        reply(&fixture.guess(USER, "abcde"), USER),
+        GameSessionEvent::CheckWordResult { tries_left: 3, .. }
+    ));
+
+    // A bank without answers cannot be played until they are uploaded
+    assert_eq!(
+        reply(
+            &fixture.start_with_bank(OTHER_USER, "themed:space"),
+            OTHER_USER
+        ),
+        GameSessionEvent::UnknownBank
+    );
+    upload(USER, "themed:space", WordList::Answers, &["orbit"]);
+    fixture.start_with_bank(OTHER_USER, "themed:space");
+    assert_eq!(
+        reply(&fixture.guess(OTHER_USER, "orbit"), OTHER_USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Win,
+            word: None
+        }
+    );
+}
+
+#[test]
+fn test_guess_root() {
+    let dictionary: Vec<_> = ["abcde", "zebra"]
+        .iter()
+        .map(|word| {
+            let word = wordle_core::validate_word(word).unwrap();
+            wordle_core::Alphabet::English.pack(&word).unwrap()
+        })
+        .collect();
+    let root = merkle_root(&dictionary).unwrap();
+    let fixture = Fixture::with_wordle_init(
+        Default::default(),
+        WordleInit {
+            banks: vec![BankInit {
+                id: "en".to_string(),
+                words: vec![SECRET.to_string()],
+                guess_root: Some(root),
+                ..Default::default()
+            }],
+        },
+    );
+    fixture.start(USER);
+
+    // Dictionary words need a proof, answers do not
+    assert_eq!(
+        reply(&fixture.guess(USER, "abcde"), USER),
+        GameSessionEvent::InvalidWord
+    );
+    let proof = merkle_proof(&dictionary, dictionary[0]).unwrap();
+    assert!(matches!(
+        reply(
+            &fixture.guess_with_proof(USER, "abcde", proof.clone()),
+            USER
+        ),
+        GameSessionEvent::CheckWordResult { tries_left: 4, .. }
+    ));
+    assert_eq!(
+        reply(&fixture.guess_with_proof(USER, "zebra", proof), USER),
+        GameSessionEvent::InvalidWord
+    );
+    assert_eq!(
+        reply(&fixture.guess(USER, "house"), USER),
+        GameSessionEvent::InvalidWord
+    );
+
+    // Without a root or uploaded guesses any word is allowed again
+    let wordle_program = fixture.system.get_program(WORDLE_PROGRAM_ID).unwrap();
+    let set_root = Action::SetGuessRoot {
+        bank: "en".to_string(),
+        root: None,
+    };
+    assert!(wordle_program
+        .send(OTHER_USER, set_root.clone())
+        .main_failed());
+    assert!(!wordle_program.send(USER, set_root).main_failed());
+    assert!(matches!(
+        reply(&fixture.guess(USER, "house"), USER),
+        GameSessionEvent::CheckWordResult { tries_left: 3, .. }
+    ));
+}
+
+#[test]
+fn test_no_repeat_words() {
+    let words = ["horse", "house", "mouse"];
+    let fixture = Fixture::with_wordle_init(
+        Default::default(),
+        WordleInit {
+            banks: vec![BankInit {
+                id: "en".to_string(),
+                words: words.iter().map(|word| word.to_string()).collect(),
+                ..Default::default()
+            }],
+        },
+    );
+    let wordle_program = fixture.system.get_program(WORDLE_PROGRAM_ID).unwrap();
+    let start = || {
+        let result = wordle_program.send(
+            USER,
+            Action::StartGame {
+                user: USER.into(),
+                bank: "en".to_string(),
+                hard_mode: false,
+            },
+        );
+        assert!(!result.main_failed());
+    };
+    let secret = || {
+        words
+            .into_iter()
+            .find(|word| {
+                let result = wordle_program.send(
+                    USER,
+                    Action::CheckWord {
+                        user: USER.into(),
+                        word: word.to_string(),
+                        proof: Vec::new(),
+                    },
+                );
+                let log = &result.log()[0];
+                matches!(
+                    Event::decode(&mut log.payload()).unwrap(),
+                    Event::WordChecked { correct_positions, .. } if correct_positions.len() == 5
+                )
+            })
+            .unwrap()
+    };
+    let played = || {
+        let state: WordleState = wordle_program.read_state(b"").unwrap();
+        state.played
+    };
+
+    // Every word comes up once before any repeats
+    let mut secrets = Vec::new();
+    for count in 1..=3 {
+        start();
+        secrets.push(secret());
+        assert_eq!(
+            played(),
+            [PlayedWords {
+                user: USER.into(),
+                bank: "en".to_string(),
+                count,
+            }]
+        );
+    }
+    secrets.sort();
+    assert_eq!(secrets, ["horse", "house", "mouse"]);
 
-    // StartGame success
-    let result = game_session_program.send(USER, GameSessionAction::StartGame);
+    // Then the history starts over
+    start();
+    assert_eq!(played()[0].count, 1);
+}
+
+#[test]
+fn test_multiple_users() {
+    let fixture = Fixture::new();
+    fixture.start(USER);
+    fixture.start(OTHER_USER);
+
+    let result = fixture.guess(USER, SECRET);
+    assert_eq!(
+        reply(&result, USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Win,
+            word: None
+        }
+    );
+
+    // The other player's game is unaffected
+    let session_info = fixture.session(OTHER_USER);
+    assert_eq!(session_info.session_status, SessionStatus::WaitUserInput);
+    assert_eq!(session_info.tries, 0);
+
+    let result = fixture.guess(OTHER_USER, "house");
+    assert!(matches!(
+        reply(&result, OTHER_USER),
+        GameSessionEvent::CheckWordResult { tries_left: 4, .. }
+    ));
+    assert_eq!(
+        fixture.status(USER),
+        SessionStatus::GameOver(GameStatus::Win)
+    );
+    assert_eq!(fixture.state().game_sessions.len(), 2);
+}
+
+#[test]
+fn test_top_up_reservations() {
+    let fixture = Fixture::new();
+
+    // Only the owner can top up
+    let result = fixture.send(
+        OTHER_USER,
+        GameSessionAction::TopUpReservations { count: 1 },
+    );
+    assert!(result.main_failed());
+
+    let result = fixture.send(USER, GameSessionAction::TopUpReservations { count: 2 });
     let log = Log::builder()
         .dest(USER)
         .source(GAME_SESSION_PROGRAM_ID)
-        .payload(GameSessionEvent::StartSuccess);
+        .payload(GameSessionEvent::ReservationsToppedUp {
+            valid_reservations: 2,
+        });
     assert!(!result.main_failed() && result.contains(&log));
+    assert_eq!(fixture.state().valid_reservations, 2);
 
-    let result = system.spend_blocks(20);
-    println!("{:?}", result);
-    let _log = Log::builder()
-        .dest(USER)
-        .source(GAME_SESSION_PROGRAM_ID)
-        .payload(GameSessionEvent::GameOver(GameStatus::Lose));
-    let state: GameSessionState = game_session_program.read_state(b"").unwrap();
-    println!("{:?}", state);
+    // Starting a game takes a reservation for its timeout
+    fixture.start(USER);
+    assert_eq!(fixture.state().valid_reservations, 1);
+}
+
+#[test]
+fn test_lose_move_timeout() {
+    let fixture = Fixture::with_config(GameSessionConfig {
+        move_timeout: Some(10),
+        ..Default::default()
+    });
+    fixture.start(USER);
+
+    // A guess in time restarts the move timer, so the first timer is outdated
+    fixture.system.spend_blocks(5);
+    fixture.guess(USER, "abcde");
+    fixture.system.spend_blocks(6);
+    assert_eq!(fixture.status(USER), SessionStatus::WaitUserInput);
+
+    let results = fixture.system.spend_blocks(5);
+    let log = game_over_log(USER);
+    assert!(results.iter().any(|result| result.contains(&log)));
+    let log = emitted_log(LifecycleEvent::TimedOut {
+        user: USER.into(),
+        game_id: fixture.session(USER).session_id,
+        move_number: Some(1),
+    });
+    assert!(results.iter().any(|result| result.contains(&log)));
+    assert_eq!(
+        fixture.status(USER),
+        SessionStatus::GameOver(GameStatus::Lose)
+    );
+}
+
+#[test]
+fn test_illegal_player_actions() {
+    let fixture = Fixture::new();
+
+    // Guessing before the game starts
+    assert!(fixture.guess(USER, SECRET).main_failed());
+
+    assert!(!fixture.start(USER).main_failed());
+
+    // Starting a second game while one is running
+    assert!(fixture.start(USER).main_failed());
+
+    assert!(!fixture.guess(USER, SECRET).main_failed());
+
+    // Guessing after the game is over
+    assert!(fixture.guess(USER, SECRET).main_failed());
+
+    // Timer actions are only accepted from the program itself
+    let session_id = fixture.session(USER).session_id;
+    fixture.start(USER);
+    let result = fixture.send(
+        USER,
+        GameSessionAction::CheckGameStatus {
+            user: USER.into(),
+            session_id: fixture.session(USER).session_id,
+        },
+    );
+    assert!(!result.main_failed());
+    assert_ne!(fixture.session(USER).session_id, session_id);
+    assert_eq!(fixture.status(USER), SessionStatus::WaitUserInput);
+}
+
+#[test]
+fn test_invalid_payload() {
+    let fixture = Fixture::new();
+    fixture.start(USER);
+
+    let mut trailing = GameSessionAction::StartGame {
+        bank: "en".to_string(),
+        mode: GameMode::Ranked,
+    }
+    .encode();
+    trailing.push(0);
+    for payload in [vec![], vec![0xff], vec![1, 20, b'h'], trailing] {
+        let result = fixture.game_session().send_bytes(USER, payload);
+        assert_eq!(reply(&result, USER), GameSessionEvent::InvalidPayload);
+    }
+    assert_eq!(fixture.status(USER), SessionStatus::WaitUserInput);
+
+    // The Wordle program rejects malformed payloads the same way
+    let wordle_program = fixture.system.get_program(WORDLE_PROGRAM_ID).unwrap();
+    let result = wordle_program.send_bytes(USER, [0xff]);
+    assert!(!result.main_failed());
+    let log = result
+        .log()
+        .iter()
+        .find(|log| log.destination() == USER.into())
+        .expect("No reply to the user");
+    assert!(matches!(
+        wordle_io::Event::decode(&mut log.payload()),
+        Ok(wordle_io::Event::InvalidPayload)
+    ));
+}
+
+#[test]
+fn test_update_config() {
+    let fixture = Fixture::new();
+    let update = |user: u64, config: GameSessionConfig| {
+        fixture.send(user, GameSessionAction::UpdateConfig(config))
+    };
+    let config = GameSessionConfig {
+        tries_limit: 6,
+        max_results: 10,
+        cleanup_interval: Some(100),
+        ..Default::default()
+    };
+
+    // Only the owner can update the config, and only with valid settings
+    assert!(update(OTHER_USER, config).main_failed());
+    for invalid in [
+        GameSessionConfig {
+            max_results: 0,
+            ..config
+        },
+        GameSessionConfig {
+            cleanup_interval: Some(0),
+            ..config
+        },
+        GameSessionConfig {
+            move_timeout: Some(0),
+            ..config
+        },
+    ] {
+        assert!(update(USER, invalid).main_failed());
+    }
+    assert_eq!(fixture.state().config, GameSessionConfig::default());
+
+    assert_eq!(
+        reply(&update(USER, config), USER),
+        GameSessionEvent::ConfigUpdated(config)
+    );
+    assert_eq!(fixture.state().config, config);
+}
+
+#[test]
+fn test_tries_limit_fixed_at_start() {
+    let fixture = Fixture::new();
+    fixture.start(USER);
+    for _ in 0..3 {
+        fixture.guess(USER, "house");
+    }
+
+    // Lowering the limit below the tries used does not change the running game
+    let config = GameSessionConfig {
+        tries_limit: 2,
+        ..Default::default()
+    };
+    fixture.send(USER, GameSessionAction::UpdateConfig(config));
+    assert!(matches!(
+        reply(&fixture.guess(USER, "house"), USER),
+        GameSessionEvent::CheckWordResult { tries_left: 1, .. }
+    ));
+    assert_eq!(
+        reply(&fixture.guess(USER, "house"), USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word: Some(SECRET.to_string())
+        }
+    );
+    assert_eq!(fixture.session(USER).tries_limit, Some(5));
+
+    // The next game gets the new limit
+    assert!(matches!(
+        reply(&fixture.start(USER), USER),
+        GameSessionEvent::StartSuccess { tries_left: 2, .. }
+    ));
+}
+
+#[test]
+fn test_hard_mode() {
+    let fixture = Fixture::with_config(GameSessionConfig {
+        hard_mode: true,
+        ..Default::default()
+    });
+    fixture.start(USER);
+    assert!(matches!(
+        reply(&fixture.guess(USER, "house"), USER),
+        GameSessionEvent::CheckWordResult { tries_left: 4, .. }
+    ));
+
+    // A guess dropping a revealed letter is refused without using a try
+    assert_eq!(
+        reply(&fixture.guess(USER, "abcde"), USER),
+        GameSessionEvent::HardModeViolated {
+            reason: "Letter h must be at position 1".to_string()
+        }
+    );
+    assert_eq!(fixture.status(USER), SessionStatus::WaitUserInput);
+    assert!(matches!(
+        reply(&fixture.guess(USER, "hoxse"), USER),
+        GameSessionEvent::CheckWordResult { tries_left: 3, .. }
+    ));
+    assert!(matches!(
+        reply(&fixture.guess(USER, SECRET), USER),
+        GameSessionEvent::GameOver {
+            status: GameStatus::Win,
+            ..
+        }
+    ));
 }
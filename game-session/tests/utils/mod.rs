@@ -0,0 +1,218 @@
+#![allow(dead_code)]
+
+use game_session_io::*;
+use gstd::prelude::{Decode, Encode};
+use gtest::{Log, Program, ProgramBuilder, RunResult, System, WasmProgram};
+use wordle_io::{BankInit, WordleInit, DEFAULT_BANK};
+
+pub const GAME_SESSION_PROGRAM_ID: u64 = 1;
+pub const WORDLE_PROGRAM_ID: u64 = 2;
+
+pub const USER: u64 = 3;
+pub const OTHER_USER: u64 = 4;
+
+/// The only secret word of the fixture's Wordle program.
+pub const SECRET: &str = "horse";
+
+/// Both programs deployed and initialized on a fresh system.
+pub struct Fixture {
+    pub system: System,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        Self::with_config(Default::default())
+    }
+
+    /// Uses the real Wordle program with a single-word default bank, so every game's secret is
+    /// `SECRET`.
+    pub fn with_config(config: GameSessionConfig) -> Self {
+        Self::with_wordle_init(
+            config,
+            WordleInit {
+                banks: vec![BankInit {
+                    id: DEFAULT_BANK.to_string(),
+                    words: vec![SECRET.to_string()],
+                    ..Default::default()
+                }],
+            },
+        )
+    }
+
+    /// Uses the real Wordle program initialized with `wordle_init`.
+    pub fn with_wordle_init(config: GameSessionConfig, wordle_init: WordleInit) -> Self {
+        let system = System::new();
+        system.init_logger();
+
+        let wordle_program =
+            ProgramBuilder::from_file("../target/wasm32-unknown-unknown/debug/wordle.opt.wasm")
+                .with_id(WORDLE_PROGRAM_ID)
+                .build(&system);
+        let result = wordle_program.send(USER, wordle_init);
+        assert!(!result.main_failed());
+
+        Self::init_game_session(system, config)
+    }
+
+    /// Replaces the Wordle program with `mock`.
+    pub fn with_wordle_mock(config: GameSessionConfig, mock: impl WasmProgram + 'static) -> Self {
+        let system = System::new();
+        system.init_logger();
+
+        let wordle_program = Program::mock_with_id(&system, WORDLE_PROGRAM_ID, mock);
+        let result = wordle_program.send_bytes(USER, []);
+        assert!(!result.main_failed());
+
+        Self::init_game_session(system, config)
+    }
+
+    fn init_game_session(system: System, config: GameSessionConfig) -> Self {
+        let game_session_program = ProgramBuilder::from_file(
+            "../target/wasm32-unknown-unknown/debug/game_session.opt.wasm",
+        )
+        .with_id(GAME_SESSION_PROGRAM_ID)
+        .build(&system);
+        let result = game_session_program.send(
+            USER,
+            GameSessionInit {
+                wordle_program_id: WORDLE_PROGRAM_ID.into(),
+                config,
+            },
+        );
+        assert!(!result.main_failed());
+
+        Self { system }
+    }
+
+    pub fn game_session(&self) -> Program<'_> {
+        self.system
+            .get_program(GAME_SESSION_PROGRAM_ID)
+            .expect("The game_session program is deployed")
+    }
+
+    pub fn send(&self, user: u64, action: GameSessionAction) -> RunResult {
+        self.game_session().send(user, action)
+    }
+
+    pub fn send_with_gas(&self, user: u64, action: GameSessionAction, gas_limit: u64) -> RunResult {
+        self.game_session()
+            .send_with_gas(user, action, gas_limit, 0)
+    }
+
+    pub fn start(&self, user: u64) -> RunResult {
+        self.start_with_bank(user, DEFAULT_BANK)
+    }
+
+    pub fn start_with_bank(&self, user: u64, bank: &str) -> RunResult {
+        self.start_with_mode(user, bank, GameMode::Ranked)
+    }
+
+    pub fn start_with_mode(&self, user: u64, bank: &str, mode: GameMode) -> RunResult {
+        self.send(
+            user,
+            GameSessionAction::StartGame {
+                bank: bank.to_string(),
+                mode,
+            },
+        )
+    }
+
+    pub fn start_practice(&self, user: u64, tries_limit: Option<u8>) -> RunResult {
+        self.start_with_mode(user, DEFAULT_BANK, GameMode::Practice { tries_limit })
+    }
+
+    pub fn guess(&self, user: u64, word: &str) -> RunResult {
+        self.guess_with_proof(user, word, Vec::new())
+    }
+
+    pub fn guess_with_proof(&self, user: u64, word: &str, proof: Vec<[u8; 32]>) -> RunResult {
+        self.send(
+            user,
+            GameSessionAction::CheckWord {
+                word: word.to_string(),
+                proof,
+            },
+        )
+    }
+
+    pub fn state(&self) -> GameSessionState {
+        self.game_session().read_state(b"").unwrap()
+    }
+
+    /// The user's session, or the default one if the user never played.
+    pub fn session(&self, user: u64) -> SessionInfo {
+        self.state()
+            .game_sessions
+            .into_iter()
+            .find(|(actor, _)| *actor == user.into())
+            .map(|(_, session_info)| session_info)
+            .unwrap_or_default()
+    }
+
+    pub fn status(&self, user: u64) -> SessionStatus {
+        self.session(user).session_status
+    }
+}
+
+/// Decodes the game_session program's reply to `user` in `result`.
+pub fn reply(result: &RunResult, user: u64) -> GameSessionEvent {
+    assert!(!result.main_failed(), "The message failed");
+    let log = result
+        .log()
+        .iter()
+        .find(|log| {
+            log.destination() == user.into() && log.source() == GAME_SESSION_PROGRAM_ID.into()
+        })
+        .expect("No reply to the user");
+    GameSessionEvent::decode(&mut log.payload()).expect("Unable to decode `GameSessionEvent`")
+}
+
+/// The message the program sends when a timer ends the user's game, revealing `SECRET`.
+pub fn game_over_log(user: u64) -> Log {
+    Log::builder()
+        .dest(user)
+        .source(GAME_SESSION_PROGRAM_ID)
+        .payload(GameSessionEvent::GameOver {
+            status: GameStatus::Lose,
+            word: Some(SECRET.to_string()),
+        })
+}
+
+/// A lifecycle event the program broadcasts to `ActorId::zero()`.
+pub fn emitted_log(event: LifecycleEvent) -> Log {
+    Log::builder()
+        .dest(0)
+        .source(GAME_SESSION_PROGRAM_ID)
+        .payload(event)
+}
+
+/// Wordle stand-in that starts games and fails every other request.
+#[derive(Debug)]
+pub struct StartOnlyWordle;
+
+impl WasmProgram for StartOnlyWordle {
+    fn init(&mut self, _payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        Ok(None)
+    }
+
+    fn handle(&mut self, payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        match WordleAction::decode(&mut payload.as_slice()) {
+            Ok(WordleAction::StartGame { user, .. }) => {
+                Ok(Some(WordleEvent::GameStarted { user }.encode()))
+            }
+            _ => Err("Wordle only starts games"),
+        }
+    }
+
+    fn handle_reply(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn handle_signal(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn state(&mut self) -> Result<Vec<u8>, &'static str> {
+        Ok(Vec::new())
+    }
+}
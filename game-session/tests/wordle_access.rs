@@ -0,0 +1,65 @@
+mod utils;
+
+use game_session_io::*;
+use utils::*;
+use wordle_io::{Action, DEFAULT_BANK};
+
+#[test]
+fn test_reveal_word_only_by_session() {
+    let fixture = Fixture::new();
+    fixture.start(USER);
+
+    // Players cannot ask Wordle for the word of a game in progress
+    let wordle_program = fixture.system.get_program(WORDLE_PROGRAM_ID).unwrap();
+    let result = wordle_program.send(USER, Action::RevealWord { user: USER.into() });
+    assert!(result.main_failed());
+    assert!(matches!(
+        reply(&fixture.guess(USER, "house"), USER),
+        GameSessionEvent::CheckWordResult { .. }
+    ));
+}
+
+#[test]
+fn test_game_only_played_by_session() {
+    let fixture = Fixture::new();
+    fixture.start(USER);
+    let wordle_program = fixture.system.get_program(WORDLE_PROGRAM_ID).unwrap();
+
+    // Players cannot guess around the session, which would skip its tries limit
+    let result = wordle_program.send(
+        USER,
+        Action::CheckWord {
+            user: USER.into(),
+            word: SECRET.to_string(),
+            proof: Vec::new(),
+        },
+    );
+    assert!(result.main_failed());
+
+    // Nor restart the game to draw another secret
+    let result = wordle_program.send(
+        USER,
+        Action::StartGame {
+            user: USER.into(),
+            bank: DEFAULT_BANK.to_string(),
+            hard_mode: false,
+        },
+    );
+    assert!(result.main_failed());
+    assert!(matches!(
+        reply(&fixture.guess(USER, "house"), USER),
+        GameSessionEvent::CheckWordResult { tries_left: 4, .. }
+    ));
+
+    // Once the session ended the game, another program can start one for the player
+    fixture.guess(USER, SECRET);
+    let result = wordle_program.send(
+        USER,
+        Action::StartGame {
+            user: USER.into(),
+            bank: DEFAULT_BANK.to_string(),
+            hard_mode: false,
+        },
+    );
+    assert!(!result.main_failed());
+}
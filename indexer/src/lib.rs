@@ -0,0 +1,408 @@
+//! Rebuilds the history of games from the `LifecycleEvent`s the `game_session` program emits
+//! into a SQLite database of games, guesses and config changes.
+//!
+//! Only lifecycle events are decoded. They cover every step of a game, so the replies to the
+//! players (`GameSessionEvent`) add nothing, and the messages between the session and the
+//! Wordle program (`WordleEvent`) never reach `ActorId::zero()`. Other messages are skipped.
+//!
+//! Events are read from a JSON dump of messages or, with the `gtest` feature, from the
+//! messages of a gtest `RunResult`. Events about games that started before the indexed
+//! history are skipped.
+
+use game_session_io::{GameSessionConfig, LifecycleEvent};
+use gstd::{ActorId, MessageId};
+#[cfg(feature = "gtest")]
+use gtest::RunResult;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+use std::path::Path;
+
+pub use rusqlite::Error;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS games (
+    game_id TEXT PRIMARY KEY,
+    user TEXT NOT NULL,
+    bank TEXT NOT NULL,
+    deadline_block INTEGER NOT NULL,
+    outcome TEXT NOT NULL DEFAULT 'active',
+    tries INTEGER NOT NULL DEFAULT 0,
+    hints INTEGER NOT NULL DEFAULT 0,
+    -- Word of the guess waiting for its evaluation
+    pending_word TEXT
+);
+CREATE INDEX IF NOT EXISTS games_by_user ON games (user);
+CREATE TABLE IF NOT EXISTS guesses (
+    game_id TEXT NOT NULL REFERENCES games (game_id),
+    number INTEGER NOT NULL,
+    word TEXT NOT NULL,
+    correct_positions BLOB NOT NULL,
+    contained_in_word BLOB NOT NULL,
+    PRIMARY KEY (game_id, number)
+);
+CREATE TABLE IF NOT EXISTS config_changes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tries_limit INTEGER NOT NULL,
+    game_timeout INTEGER NOT NULL,
+    move_timeout INTEGER
+);
+";
+
+/// A message of a program run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub source: ActorId,
+    pub destination: ActorId,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct DumpedEntry {
+    source: String,
+    destination: String,
+    payload: String,
+}
+
+/// Parses a JSON dump of messages: an array of objects with `source`, `destination` and
+/// `payload`, all hex strings with an optional `0x` prefix.
+pub fn parse_dump(json: &str) -> Result<Vec<LogEntry>, String> {
+    let entries: Vec<DumpedEntry> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let id = |field: &str, value: &str| {
+                parse_id(value).ok_or(format!("Entry {}: invalid `{}`", i, field))
+            };
+            Ok(LogEntry {
+                source: id("source", &entry.source)?.into(),
+                destination: id("destination", &entry.destination)?.into(),
+                payload: from_hex(&entry.payload)
+                    .ok_or(format!("Entry {}: invalid `payload`", i))?,
+            })
+        })
+        .collect()
+}
+
+/// `0x`-prefixed lowercase hex of `bytes`, the way ids are stored.
+pub fn to_hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("0x{}", digits)
+}
+
+/// Bytes of a hex string with an optional `0x` prefix.
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// A 32-byte actor or message id in hex.
+pub fn parse_id(hex: &str) -> Option<[u8; 32]> {
+    from_hex(hex)?.try_into().ok()
+}
+
+/// How a game ended, if it did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Active,
+    Won,
+    Lost,
+    TimedOut,
+    GaveUp,
+}
+
+impl Outcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Won => "won",
+            Self::Lost => "lost",
+            Self::TimedOut => "timed_out",
+            Self::GaveUp => "gave_up",
+        }
+    }
+
+    fn parse(outcome: &str) -> Self {
+        match outcome {
+            "won" => Self::Won,
+            "lost" => Self::Lost,
+            "timed_out" => Self::TimedOut,
+            "gave_up" => Self::GaveUp,
+            _ => Self::Active,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub game_id: MessageId,
+    pub user: ActorId,
+    pub bank: String,
+    pub deadline_block: u32,
+    pub outcome: Outcome,
+    pub tries: u8,
+    pub hints: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuessRecord {
+    pub number: u8,
+    pub word: String,
+    pub correct_positions: Vec<u8>,
+    pub contained_in_word: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerStats {
+    pub user: ActorId,
+    pub games: u32,
+    pub wins: u32,
+    /// Wins without hints.
+    pub pure_wins: u32,
+    /// Games lost on tries or given up.
+    pub losses: u32,
+    pub timeouts: u32,
+    /// Average tries of the won games.
+    pub average_tries: Option<f64>,
+}
+
+const STATS_QUERY: &str = "
+SELECT user, COUNT(*), SUM(outcome = 'won'), SUM(outcome = 'won' AND hints = 0),
+    SUM(outcome IN ('lost', 'gave_up')), SUM(outcome = 'timed_out'),
+    AVG(CASE WHEN outcome = 'won' THEN tries END)
+FROM games";
+
+fn hex_id(id: &impl AsRef<[u8]>) -> String {
+    to_hex(id.as_ref())
+}
+
+fn actor_id(hex: String) -> ActorId {
+    parse_id(&hex).expect("Stored ids are valid").into()
+}
+
+fn player_stats(row: &rusqlite::Row) -> rusqlite::Result<PlayerStats> {
+    Ok(PlayerStats {
+        user: actor_id(row.get(0)?),
+        games: row.get(1)?,
+        wins: row.get(2)?,
+        pure_wins: row.get(3)?,
+        losses: row.get(4)?,
+        timeouts: row.get(5)?,
+        average_tries: row.get(6)?,
+    })
+}
+
+/// The database and the program whose events it indexes.
+pub struct Indexer {
+    conn: Connection,
+    program: Option<ActorId>,
+}
+
+impl Indexer {
+    /// Opens or creates the database at `path`.
+    ///
+    /// Only events of `program` are indexed; with `None`, any message to `ActorId::zero()`
+    /// that decodes as a `LifecycleEvent` is.
+    pub fn open(path: impl AsRef<Path>, program: Option<ActorId>) -> rusqlite::Result<Self> {
+        Self::new(Connection::open(path)?, program)
+    }
+
+    pub fn in_memory(program: Option<ActorId>) -> rusqlite::Result<Self> {
+        Self::new(Connection::open_in_memory()?, program)
+    }
+
+    fn new(conn: Connection, program: Option<ActorId>) -> rusqlite::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn, program })
+    }
+
+    /// Indexes `entry` if it is an event of the program; returns whether it was.
+    pub fn ingest(&mut self, entry: &LogEntry) -> rusqlite::Result<bool> {
+        if entry.destination != ActorId::zero()
+            || self.program.is_some_and(|program| entry.source != program)
+        {
+            return Ok(false);
+        }
+        match LifecycleEvent::from_payload(&entry.payload) {
+            Some(event) => self.apply(&event).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Indexes the events among `entries` in a single transaction; returns how many there were.
+    pub fn ingest_all<'a>(
+        &mut self,
+        entries: impl IntoIterator<Item = &'a LogEntry>,
+    ) -> rusqlite::Result<usize> {
+        self.conn.execute_batch("BEGIN")?;
+        let mut count = 0;
+        for entry in entries {
+            match self.ingest(entry) {
+                Ok(indexed) => count += indexed as usize,
+                Err(error) => {
+                    self.conn.execute_batch("ROLLBACK")?;
+                    return Err(error);
+                }
+            }
+        }
+        self.conn.execute_batch("COMMIT")?;
+        Ok(count)
+    }
+
+    /// Indexes the events among the messages of a gtest run.
+    #[cfg(feature = "gtest")]
+    pub fn ingest_result(&mut self, result: &RunResult) -> rusqlite::Result<usize> {
+        let entries: Vec<LogEntry> = result
+            .log()
+            .iter()
+            .map(|log| LogEntry {
+                source: <[u8; 32]>::try_from(log.source().as_ref())
+                    .expect("Ids are 32 bytes")
+                    .into(),
+                destination: <[u8; 32]>::try_from(log.destination().as_ref())
+                    .expect("Ids are 32 bytes")
+                    .into(),
+                payload: log.payload().to_vec(),
+            })
+            .collect();
+        self.ingest_all(&entries)
+    }
+
+    pub fn apply(&mut self, event: &LifecycleEvent) -> rusqlite::Result<()> {
+        let set_outcome = |game_id: &MessageId, outcome: Outcome, tries: Option<u8>| {
+            self.conn.execute(
+                "UPDATE games SET outcome = ?2, tries = COALESCE(?3, tries), pending_word = NULL
+                 WHERE game_id = ?1",
+                params![hex_id(game_id), outcome.as_str(), tries],
+            )
+        };
+        match event {
+            LifecycleEvent::GameStarted {
+                user,
+                game_id,
+                bank,
+                deadline_block,
+            } => self.conn.execute(
+                "INSERT OR IGNORE INTO games (game_id, user, bank, deadline_block)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![hex_id(game_id), hex_id(user), bank, deadline_block],
+            ),
+            LifecycleEvent::GuessSubmitted { game_id, word, .. } => self.conn.execute(
+                "UPDATE games SET pending_word = ?2 WHERE game_id = ?1",
+                params![hex_id(game_id), word],
+            ),
+            LifecycleEvent::GuessEvaluated {
+                game_id,
+                tries,
+                correct_positions,
+                contained_in_word,
+                ..
+            } => {
+                self.conn.execute(
+                    "INSERT OR IGNORE INTO guesses
+                     SELECT game_id, ?2, pending_word, ?3, ?4 FROM games
+                     WHERE game_id = ?1 AND pending_word IS NOT NULL",
+                    params![hex_id(game_id), tries, correct_positions, contained_in_word],
+                )?;
+                self.conn.execute(
+                    "UPDATE games SET tries = ?2, pending_word = NULL WHERE game_id = ?1",
+                    params![hex_id(game_id), tries],
+                )
+            }
+            LifecycleEvent::GameWon { game_id, tries, .. } => {
+                set_outcome(game_id, Outcome::Won, Some(*tries))
+            }
+            LifecycleEvent::GameLost { game_id, tries, .. } => {
+                set_outcome(game_id, Outcome::Lost, Some(*tries))
+            }
+            LifecycleEvent::TimedOut { game_id, .. } => {
+                set_outcome(game_id, Outcome::TimedOut, None)
+            }
+            LifecycleEvent::GaveUp { game_id, tries, .. } => {
+                set_outcome(game_id, Outcome::GaveUp, Some(*tries))
+            }
+            LifecycleEvent::HintRevealed { game_id, tries, .. } => self.conn.execute(
+                "UPDATE games SET hints = hints + 1, tries = ?2 WHERE game_id = ?1",
+                params![hex_id(game_id), tries],
+            ),
+            LifecycleEvent::ConfigChanged(GameSessionConfig {
+                tries_limit,
+                game_timeout,
+                move_timeout,
+                ..
+            }) => self.conn.execute(
+                "INSERT INTO config_changes (tries_limit, game_timeout, move_timeout)
+                 VALUES (?1, ?2, ?3)",
+                params![tries_limit, game_timeout, move_timeout],
+            ),
+        }
+        .map(|_| ())
+    }
+
+    /// The user's games, the latest first.
+    pub fn games(&self, user: ActorId) -> rusqlite::Result<Vec<GameRecord>> {
+        let mut statement = self.conn.prepare(
+            "SELECT game_id, user, bank, deadline_block, outcome, tries, hints FROM games
+             WHERE user = ?1 ORDER BY rowid DESC",
+        )?;
+        let games = statement.query_map([hex_id(&user)], |row| {
+            Ok(GameRecord {
+                game_id: parse_id(&row.get::<_, String>(0)?)
+                    .expect("Stored ids are valid")
+                    .into(),
+                user: actor_id(row.get(1)?),
+                bank: row.get(2)?,
+                deadline_block: row.get(3)?,
+                outcome: Outcome::parse(&row.get::<_, String>(4)?),
+                tries: row.get(5)?,
+                hints: row.get(6)?,
+            })
+        })?;
+        games.collect()
+    }
+
+    /// The evaluated guesses of the game, in order.
+    pub fn guesses(&self, game_id: MessageId) -> rusqlite::Result<Vec<GuessRecord>> {
+        let mut statement = self.conn.prepare(
+            "SELECT number, word, correct_positions, contained_in_word FROM guesses
+             WHERE game_id = ?1 ORDER BY number",
+        )?;
+        let guesses = statement.query_map([hex_id(&game_id)], |row| {
+            Ok(GuessRecord {
+                number: row.get(0)?,
+                word: row.get(1)?,
+                correct_positions: row.get(2)?,
+                contained_in_word: row.get(3)?,
+            })
+        })?;
+        guesses.collect()
+    }
+
+    /// Stats of the user, or `None` if they never played.
+    pub fn player_stats(&self, user: ActorId) -> rusqlite::Result<Option<PlayerStats>> {
+        self.conn
+            .query_row(
+                &format!("{} WHERE user = ?1 GROUP BY user", STATS_QUERY),
+                [hex_id(&user)],
+                player_stats,
+            )
+            .optional()
+    }
+
+    /// The players with the most pure wins, then the most wins, fewer average tries breaking
+    /// ties.
+    pub fn leaderboard(&self, limit: u32) -> rusqlite::Result<Vec<PlayerStats>> {
+        let mut statement = self.conn.prepare(&format!(
+            "{} GROUP BY user ORDER BY 4 DESC, 3 DESC, 7 ASC LIMIT ?1",
+            STATS_QUERY
+        ))?;
+        let stats = statement.query_map([limit], player_stats)?;
+        stats.collect()
+    }
+}
@@ -0,0 +1,144 @@
+use std::{env, fs, process};
+use wordle_indexer::*;
+
+const USAGE: &str = "\
+Usage: wordle-indexer <db> ingest <dump.json> [--program <id>]
+       wordle-indexer <db> games <user>
+       wordle-indexer <db> guesses <game-id>
+       wordle-indexer <db> stats <user>
+       wordle-indexer <db> leaderboard [<n>]
+
+Indexes the lifecycle events of the game_session program into the SQLite
+database <db>, creating it if needed, and queries it.
+
+`ingest` reads a JSON array of messages, each an object with `source`,
+`destination` and `payload` in hex. Only messages to the zero address are
+indexed, and with `--program` only those from the program <id>.
+
+Ids are 32 bytes in hex.";
+
+enum Command {
+    Ingest {
+        dump: String,
+        program: Option<[u8; 32]>,
+    },
+    Games([u8; 32]),
+    Guesses([u8; 32]),
+    Stats([u8; 32]),
+    Leaderboard(u32),
+}
+
+fn id(arg: Option<String>, what: &str) -> Result<[u8; 32], String> {
+    let arg = arg.ok_or(format!("Missing {}", what))?;
+    parse_id(&arg).ok_or(format!("`{}` is not a valid {}", arg, what))
+}
+
+fn parse_args() -> Result<(String, Command), String> {
+    let mut args = env::args().skip(1);
+    let db = args.next().ok_or("Missing database file")?;
+    if db == "-h" || db == "--help" {
+        return Err(String::new());
+    }
+    let command = match args.next().as_deref() {
+        Some("ingest") => {
+            let dump = args.next().ok_or("Missing dump file")?;
+            let program = match args.next().as_deref() {
+                Some("--program") => Some(id(args.next(), "program id")?),
+                Some(arg) => return Err(format!("Unexpected argument `{}`", arg)),
+                None => None,
+            };
+            Command::Ingest { dump, program }
+        }
+        Some("games") => Command::Games(id(args.next(), "user id")?),
+        Some("guesses") => Command::Guesses(id(args.next(), "game id")?),
+        Some("stats") => Command::Stats(id(args.next(), "user id")?),
+        Some("leaderboard") => Command::Leaderboard(match args.next() {
+            Some(n) => n.parse().map_err(|_| format!("`{}` is not a number", n))?,
+            None => 10,
+        }),
+        Some(command) => return Err(format!("Unknown command `{}`", command)),
+        None => return Err("Missing command".to_string()),
+    };
+    if let Some(arg) = args.next() {
+        return Err(format!("Unexpected argument `{}`", arg));
+    }
+    Ok((db, command))
+}
+
+fn print_stats(stats: &PlayerStats) {
+    let average_tries = stats
+        .average_tries
+        .map_or("-".to_string(), |tries| format!("{:.2}", tries));
+    println!(
+        "{}  games {}  won {} ({} pure)  lost {}  timed out {}  average tries {}",
+        to_hex(stats.user.as_ref()),
+        stats.games,
+        stats.wins,
+        stats.pure_wins,
+        stats.losses,
+        stats.timeouts,
+        average_tries
+    );
+}
+
+fn run(db: String, command: Command) -> Result<(), String> {
+    let program = match command {
+        Command::Ingest { program, .. } => program.map(Into::into),
+        _ => None,
+    };
+    let mut indexer = Indexer::open(&db, program).map_err(|e| format!("{}: {}", db, e))?;
+    match command {
+        Command::Ingest { dump, .. } => {
+            let json = fs::read_to_string(&dump).map_err(|e| format!("{}: {}", dump, e))?;
+            let entries = parse_dump(&json).map_err(|e| format!("{}: {}", dump, e))?;
+            let count = indexer.ingest_all(&entries).map_err(|e| e.to_string())?;
+            println!("Indexed {} of {} messages", count, entries.len());
+        }
+        Command::Games(user) => {
+            for game in indexer.games(user.into()).map_err(|e| e.to_string())? {
+                println!(
+                    "{}  {}  {}  {} tries  {} hints  deadline {}",
+                    to_hex(game.game_id.as_ref()),
+                    game.bank,
+                    game.outcome.as_str(),
+                    game.tries,
+                    game.hints,
+                    game.deadline_block
+                );
+            }
+        }
+        Command::Guesses(game_id) => {
+            for guess in indexer.guesses(game_id.into()).map_err(|e| e.to_string())? {
+                println!(
+                    "{}. {}  correct {:?}  present {:?}",
+                    guess.number, guess.word, guess.correct_positions, guess.contained_in_word
+                );
+            }
+        }
+        Command::Stats(user) => {
+            match indexer
+                .player_stats(user.into())
+                .map_err(|e| e.to_string())?
+            {
+                Some(stats) => print_stats(&stats),
+                None => println!("No games"),
+            }
+        }
+        Command::Leaderboard(limit) => {
+            for stats in indexer.leaderboard(limit).map_err(|e| e.to_string())? {
+                print_stats(&stats);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = parse_args().and_then(|(db, command)| run(db, command)) {
+        if !error.is_empty() {
+            eprintln!("{}\n", error);
+        }
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,76 @@
+use game_session_io::{GameMode, GameSessionAction, GameSessionInit};
+use gstd::ActorId;
+use gtest::{ProgramBuilder, System};
+use wordle_indexer::*;
+use wordle_io::{BankInit, WordleInit, DEFAULT_BANK};
+
+const GAME_SESSION_PROGRAM_ID: u64 = 1;
+const WORDLE_PROGRAM_ID: u64 = 2;
+const USER: u64 = 3;
+
+#[test]
+fn test_ingest_result() {
+    let system = System::new();
+    let wordle_program =
+        ProgramBuilder::from_file("../target/wasm32-unknown-unknown/debug/wordle.opt.wasm")
+            .with_id(WORDLE_PROGRAM_ID)
+            .build(&system);
+    let result = wordle_program.send(
+        USER,
+        WordleInit {
+            banks: vec![BankInit {
+                id: DEFAULT_BANK.to_string(),
+                words: vec!["horse".to_string()],
+                ..Default::default()
+            }],
+        },
+    );
+    assert!(!result.main_failed());
+    let game_session_program =
+        ProgramBuilder::from_file("../target/wasm32-unknown-unknown/debug/game_session.opt.wasm")
+            .with_id(GAME_SESSION_PROGRAM_ID)
+            .build(&system);
+    let result = game_session_program.send(
+        USER,
+        GameSessionInit {
+            wordle_program_id: WORDLE_PROGRAM_ID.into(),
+            config: Default::default(),
+        },
+    );
+    assert!(!result.main_failed());
+
+    let mut indexer = Indexer::in_memory(Some(GAME_SESSION_PROGRAM_ID.into())).unwrap();
+    let start = game_session_program.send(
+        USER,
+        GameSessionAction::StartGame {
+            bank: DEFAULT_BANK.to_string(),
+            mode: GameMode::Ranked,
+        },
+    );
+    assert_eq!(indexer.ingest_result(&start).unwrap(), 1);
+    for word in ["house", "horse"] {
+        let result = game_session_program.send(
+            USER,
+            GameSessionAction::CheckWord {
+                word: word.to_string(),
+                proof: vec![],
+            },
+        );
+        indexer.ingest_result(&result).unwrap();
+    }
+
+    let user: ActorId = USER.into();
+    let games = indexer.games(user).unwrap();
+    assert_eq!(games.len(), 1);
+    let game = &games[0];
+    assert_eq!((game.outcome, game.tries), (Outcome::Won, 2));
+    assert_eq!(
+        indexer
+            .guesses(game.game_id)
+            .unwrap()
+            .iter()
+            .map(|guess| guess.word.as_str())
+            .collect::<Vec<_>>(),
+        ["house", "horse"]
+    );
+}
@@ -0,0 +1,213 @@
+use game_session_io::{GameSessionConfig, LifecycleEvent};
+use gstd::{prelude::Encode, ActorId, MessageId};
+use wordle_indexer::*;
+
+const USER: u64 = 3;
+const OTHER_USER: u64 = 4;
+const PROGRAM: [u8; 32] = [1; 32];
+
+fn evaluated(game_id: MessageId, tries: u8, correct_positions: Vec<u8>) -> LifecycleEvent {
+    LifecycleEvent::GuessEvaluated {
+        user: USER.into(),
+        game_id,
+        tries,
+        correct_positions,
+        contained_in_word: vec![],
+    }
+}
+
+fn submitted(game_id: MessageId, word: &str) -> LifecycleEvent {
+    LifecycleEvent::GuessSubmitted {
+        user: USER.into(),
+        game_id,
+        word: word.to_string(),
+    }
+}
+
+fn started(user: ActorId, game_id: MessageId) -> LifecycleEvent {
+    LifecycleEvent::GameStarted {
+        user,
+        game_id,
+        bank: "en".to_string(),
+        deadline_block: 20,
+    }
+}
+
+#[test]
+fn test_history() {
+    let mut indexer = Indexer::in_memory(None).unwrap();
+    let (user, other_user): (ActorId, ActorId) = (USER.into(), OTHER_USER.into());
+    let (won, timed_out, lost, gave_up, hinted) = (
+        MessageId::from([1; 32]),
+        MessageId::from([2; 32]),
+        MessageId::from([3; 32]),
+        MessageId::from([4; 32]),
+        MessageId::from([5; 32]),
+    );
+    let events = [
+        started(user, won),
+        submitted(won, "abcde"),
+        evaluated(won, 1, vec![4]),
+        // Rejected by Wordle, so never evaluated
+        submitted(won, "abc"),
+        submitted(won, "horse"),
+        evaluated(won, 2, vec![0, 1, 2, 3, 4]),
+        LifecycleEvent::GameWon {
+            user,
+            game_id: won,
+            tries: 2,
+        },
+        started(user, timed_out),
+        LifecycleEvent::TimedOut {
+            user,
+            game_id: timed_out,
+            move_number: None,
+        },
+        started(other_user, lost),
+        LifecycleEvent::GameLost {
+            user: other_user,
+            game_id: lost,
+            tries: 5,
+        },
+        started(other_user, gave_up),
+        LifecycleEvent::GaveUp {
+            user: other_user,
+            game_id: gave_up,
+            tries: 1,
+        },
+        started(other_user, hinted),
+        LifecycleEvent::HintRevealed {
+            user: other_user,
+            game_id: hinted,
+            position: 2,
+            tries: 1,
+        },
+        LifecycleEvent::GameWon {
+            user: other_user,
+            game_id: hinted,
+            tries: 1,
+        },
+        // A game that started before the indexed history
+        LifecycleEvent::GameWon {
+            user,
+            game_id: MessageId::from([9; 32]),
+            tries: 1,
+        },
+    ];
+    for event in &events {
+        indexer.apply(event).unwrap();
+    }
+
+    let games = indexer.games(user).unwrap();
+    assert_eq!(
+        games
+            .iter()
+            .map(|game| (game.game_id, game.outcome, game.tries))
+            .collect::<Vec<_>>(),
+        [(timed_out, Outcome::TimedOut, 0), (won, Outcome::Won, 2)]
+    );
+
+    let guesses = indexer.guesses(won).unwrap();
+    assert_eq!(
+        guesses,
+        [
+            GuessRecord {
+                number: 1,
+                word: "abcde".to_string(),
+                correct_positions: vec![4],
+                contained_in_word: vec![],
+            },
+            GuessRecord {
+                number: 2,
+                word: "horse".to_string(),
+                correct_positions: vec![0, 1, 2, 3, 4],
+                contained_in_word: vec![],
+            },
+        ]
+    );
+
+    assert_eq!(
+        indexer.player_stats(user).unwrap(),
+        Some(PlayerStats {
+            user,
+            games: 2,
+            wins: 1,
+            pure_wins: 1,
+            losses: 0,
+            timeouts: 1,
+            average_tries: Some(2.0),
+        })
+    );
+    let other_stats = indexer.player_stats(other_user).unwrap().unwrap();
+    assert_eq!(
+        (other_stats.wins, other_stats.pure_wins, other_stats.losses),
+        (1, 0, 2)
+    );
+    assert_eq!(indexer.games(other_user).unwrap()[0].hints, 1);
+    assert_eq!(indexer.player_stats(5u64.into()).unwrap(), None);
+
+    // Pure wins rank first, even with more average tries
+    let leaderboard = indexer.leaderboard(10).unwrap();
+    assert_eq!(
+        leaderboard
+            .iter()
+            .map(|stats| stats.user)
+            .collect::<Vec<_>>(),
+        [user, other_user]
+    );
+    assert_eq!(indexer.leaderboard(1).unwrap().len(), 1);
+}
+
+#[test]
+fn test_ingest_dump() {
+    let mut indexer = Indexer::in_memory(Some(PROGRAM.into())).unwrap();
+    let user: ActorId = USER.into();
+    let game_id = MessageId::from([1; 32]);
+    let entry = |source: [u8; 32], destination: ActorId, event: LifecycleEvent| {
+        format!(
+            r#"{{"source": "{}", "destination": "{}", "payload": "{}"}}"#,
+            to_hex(&source),
+            to_hex(destination.as_ref()),
+            to_hex(&event.encode())
+        )
+    };
+    let config = GameSessionConfig {
+        tries_limit: 6,
+        ..Default::default()
+    };
+    let json = format!(
+        "[{}]",
+        [
+            entry(PROGRAM, ActorId::zero(), started(user, game_id)),
+            // Not broadcast, or not by the indexed program
+            entry(PROGRAM, user, LifecycleEvent::ConfigChanged(config)),
+            entry(
+                [2; 32],
+                ActorId::zero(),
+                LifecycleEvent::ConfigChanged(config)
+            ),
+            entry(
+                PROGRAM,
+                ActorId::zero(),
+                LifecycleEvent::ConfigChanged(config)
+            ),
+        ]
+        .join(",")
+    );
+
+    let entries = parse_dump(&json).unwrap();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(indexer.ingest_all(&entries).unwrap(), 2);
+    assert_eq!(indexer.games(user).unwrap()[0].outcome, Outcome::Active);
+
+    // Malformed payloads are skipped
+    let junk = LogEntry {
+        source: PROGRAM.into(),
+        destination: ActorId::zero(),
+        payload: vec![0xff],
+    };
+    assert!(!indexer.ingest(&junk).unwrap());
+
+    assert!(parse_dump(r#"[{"source": "0x01", "destination": "00", "payload": ""}]"#).is_err());
+    assert!(parse_dump("{}").is_err());
+}
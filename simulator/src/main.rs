@@ -0,0 +1,242 @@
+//! Plays many solver-driven games against the real wasm programs and reports statistics.
+
+use game_session_io::*;
+use gstd::prelude::Encode;
+use std::{env, fs, path::PathBuf, process, str::FromStr};
+use wordle_client::*;
+use wordle_core::{Alphabet, Word, BANK_OF_WORDS};
+use wordle_io::{BankInit, WordleInit, DEFAULT_BANK};
+use wordle_solver::*;
+
+const USAGE: &str = "\
+Usage: wordle-simulator [--games <n>] [--players <n>] [--tries <n>]
+                        [--words <word-file>] [--alphabet <name>]
+                        [--report-every <n>] [--wasm-dir <dir>]
+
+Plays <games> games spread over <players> players, guessing with the solver.
+`--tries` sets the session's tries limit, `--words` the bank of words used by
+both the Wordle program and the solver (the default bank otherwise), spelled
+in the `--alphabet`: en (default), es, de, de-translit or uk.";
+
+struct Args {
+    games: u32,
+    players: u32,
+    tries: u8,
+    words: Option<PathBuf>,
+    alphabet: Alphabet,
+    report_every: u32,
+    wasm_dir: PathBuf,
+}
+
+fn number<T: FromStr>(value: String) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid number `{}`", value))
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut parsed = Args {
+        games: 1000,
+        players: 100,
+        tries: GameSessionConfig::default().tries_limit,
+        words: None,
+        alphabet: Alphabet::English,
+        report_every: 250,
+        wasm_dir: default_wasm_dir(),
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--games" => parsed.games = number(value()?)?,
+            "--players" => parsed.players = number(value()?)?,
+            "--tries" => parsed.tries = number(value()?)?,
+            "--report-every" => parsed.report_every = number(value()?)?,
+            "--words" => parsed.words = Some(value()?.into()),
+            "--alphabet" => {
+                let name = value()?;
+                parsed.alphabet = Alphabet::from_name(&name)
+                    .ok_or_else(|| format!("Unknown alphabet `{}`", name))?
+            }
+            "--wasm-dir" => parsed.wasm_dir = value()?.into(),
+            "-h" | "--help" => return Err(String::new()),
+            _ => return Err(format!("Unexpected argument `{}`", arg)),
+        }
+    }
+    if parsed.players == 0 || parsed.report_every == 0 {
+        return Err("--players and --report-every must be positive".to_string());
+    }
+    if parsed.words.is_none() && parsed.alphabet != Alphabet::English {
+        return Err("--alphabet needs --words".to_string());
+    }
+    Ok(parsed)
+}
+
+#[derive(Default)]
+struct Stats {
+    wins: u32,
+    losses: u32,
+    /// Number of wins per tries used, indexed from one try.
+    tries_histogram: Vec<u32>,
+    start_gas: Vec<u64>,
+    check_gas: Vec<u64>,
+}
+
+impl Stats {
+    fn games(&self) -> u32 {
+        self.wins + self.losses
+    }
+
+    fn record_win(&mut self, tries: usize) {
+        self.wins += 1;
+        if self.tries_histogram.len() < tries {
+            self.tries_histogram.resize(tries, 0);
+        }
+        self.tries_histogram[tries - 1] += 1;
+    }
+
+    fn average_tries(&self) -> f64 {
+        let total: u64 = self
+            .tries_histogram
+            .iter()
+            .enumerate()
+            .map(|(i, wins)| (i as u64 + 1) * *wins as u64)
+            .sum();
+        total as f64 / self.wins.max(1) as f64
+    }
+}
+
+fn average(values: &[u64]) -> u64 {
+    values.iter().sum::<u64>() / values.len().max(1) as u64
+}
+
+fn next_guess(words: &[Word], history: &[Turn]) -> Result<Word, String> {
+    let remaining = candidates(words, history);
+    match remaining.as_slice() {
+        [] => Err("No candidates left; is the word list the program's bank?".to_string()),
+        [word] => Ok(*word),
+        _ => Ok(suggestions(words, &remaining, 1)[0].0),
+    }
+}
+
+fn play(game: &LocalGame, user: u64, words: &[Word], stats: &mut Stats) -> Result<(), String> {
+    let (reply, gas_burned) = game.send_measured(
+        user,
+        GameSessionAction::StartGame {
+            bank: DEFAULT_BANK.to_string(),
+            mode: GameMode::Ranked,
+        },
+    );
+    stats.start_gas.push(gas_burned);
+    if !matches!(reply?, GameSessionEvent::StartSuccess { .. }) {
+        return Err("Unexpected reply to StartGame".to_string());
+    }
+
+    let mut history = Vec::new();
+    loop {
+        let guess = next_guess(words, &history)?;
+        let (reply, gas_burned) = game.send_measured(
+            user,
+            GameSessionAction::CheckWord {
+                word: word_to_string(&guess),
+                proof: Vec::new(),
+            },
+        );
+        stats.check_gas.push(gas_burned);
+        match reply? {
+            GameSessionEvent::CheckWordResult {
+                correct_positions,
+                contained_in_word,
+                ..
+            } => history.push(Turn::from_positions(
+                guess,
+                &correct_positions,
+                &contained_in_word,
+            )),
+            GameSessionEvent::GameOver {
+                status: GameStatus::Win,
+                ..
+            } => {
+                stats.record_win(history.len() + 1);
+                return Ok(());
+            }
+            GameSessionEvent::GameOver {
+                status: GameStatus::Lose,
+                ..
+            } => {
+                stats.losses += 1;
+                return Ok(());
+            }
+            event => return Err(format!("Unexpected reply to CheckWord: {:?}", event)),
+        }
+    }
+}
+
+fn report_state(game: &LocalGame, stats: &Stats) {
+    let state = game.state();
+    println!(
+        "after {:>6} games: {:>5} sessions, state {:>8} bytes",
+        stats.games(),
+        state.game_sessions.len(),
+        state.encode().len()
+    );
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let words = match &args.words {
+        Some(path) => {
+            let text =
+                fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            parse_words(&text, args.alphabet).map_err(|e| e.to_string())?
+        }
+        None => BANK_OF_WORDS.to_vec(),
+    };
+    let config = GameSessionConfig {
+        tries_limit: args.tries,
+        ..Default::default()
+    };
+    let wordle_init = WordleInit {
+        banks: vec![BankInit {
+            id: DEFAULT_BANK.to_string(),
+            alphabet: args.alphabet.into(),
+            words: words.iter().map(word_to_string).collect(),
+            ..Default::default()
+        }],
+    };
+    let game = LocalGame::new(&args.wasm_dir, wordle_init, config)?;
+    let mut stats = Stats::default();
+
+    for i in 0..args.games {
+        play(&game, USER + (i % args.players) as u64, &words, &mut stats)?;
+        if (i + 1) % args.report_every == 0 {
+            report_state(&game, &stats);
+        }
+    }
+
+    println!();
+    println!("words:          {}", words.len());
+    println!("tries limit:    {}", args.tries);
+    println!("games:          {}", stats.games());
+    println!(
+        "win rate:       {:.2}%",
+        100.0 * stats.wins as f64 / stats.games().max(1) as f64
+    );
+    println!("average tries:  {:.3} (wins only)", stats.average_tries());
+    for (i, wins) in stats.tries_histogram.iter().enumerate() {
+        println!("  {} tries: {}", i + 1, wins);
+    }
+    println!("gas StartGame:  {} (average)", average(&stats.start_gas));
+    println!("gas CheckWord:  {} (average)", average(&stats.check_gas));
+    report_state(&game, &stats);
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = parse_args().and_then(run) {
+        if !error.is_empty() {
+            eprintln!("{}\n", error);
+        }
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
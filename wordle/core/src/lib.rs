@@ -0,0 +1,394 @@
+#![no_std]
+
+//! Pure Wordle rules shared by the on-chain programs and off-chain tools.
+
+use core::fmt;
+
+/// Number of letters in a word.
+pub const WORD_LENGTH: usize = 5;
+
+/// A validated word: one letter of its bank's alphabet per position.
+pub type Word = [char; WORD_LENGTH];
+
+/// A word packed by `Alphabet::pack`.
+pub type PackedWord = u32;
+
+/// Secret words the Wordle program picks from.
+pub const BANK_OF_WORDS: [Word; 3] = [ascii(b"house"), ascii(b"human"), ascii(b"horse")];
+
+const fn ascii(bytes: &[u8; WORD_LENGTH]) -> Word {
+    let mut word = ['\0'; WORD_LENGTH];
+    let mut i = 0;
+    while i < WORD_LENGTH {
+        word[i] = bytes[i] as char;
+        i += 1;
+    }
+    word
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordError {
+    InvalidLength,
+    InvalidLetter,
+}
+
+impl fmt::Display for WordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WordError::InvalidLength => write!(f, "The word must have {} letters", WORD_LENGTH),
+            WordError::InvalidLetter => {
+                write!(
+                    f,
+                    "The word must consist of lowercase letters of the alphabet"
+                )
+            }
+        }
+    }
+}
+
+/// Letters a bank of words is spelled with.
+///
+/// Words are compared letter by letter, so the alphabet decides what counts as one
+/// letter: Spanish `ñ` is a letter of its own while accents are dropped, and German
+/// either keeps `ä`, `ö`, `ü` and `ß` or spells them out as `ae`, `oe`, `ue` and `ss`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alphabet {
+    #[default]
+    English,
+    Spanish,
+    /// Umlauts and `ß` are letters of their own.
+    German,
+    /// Umlauts and `ß` are spelled out, as on keyboards without them.
+    GermanTransliterated,
+    Ukrainian,
+}
+
+impl Alphabet {
+    /// The alphabet named `en`, `es`, `de`, `de-translit` or `uk`, as the tools take it.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "en" => Some(Alphabet::English),
+            "es" => Some(Alphabet::Spanish),
+            "de" => Some(Alphabet::German),
+            "de-translit" => Some(Alphabet::GermanTransliterated),
+            "uk" => Some(Alphabet::Ukrainian),
+            _ => None,
+        }
+    }
+
+    /// The letters of the alphabet in alphabetical order.
+    pub fn letters(&self) -> &'static str {
+        match self {
+            Alphabet::English | Alphabet::GermanTransliterated => "abcdefghijklmnopqrstuvwxyz",
+            Alphabet::Spanish => "abcdefghijklmnñopqrstuvwxyz",
+            Alphabet::German => "abcdefghijklmnopqrstuvwxyzßäöü",
+            Alphabet::Ukrainian => "абвгґдеєжзиіїйклмнопрстуфхцчшщьюя",
+        }
+    }
+
+    /// Position of `letter` in the alphabet.
+    pub fn index_of(&self, letter: char) -> Option<usize> {
+        self.letters().chars().position(|c| c == letter)
+    }
+
+    /// Bits a letter takes in a `PackedWord`: five, or six for alphabets of more than 32 letters.
+    pub fn letter_bits(&self) -> u32 {
+        if self.letters().chars().count() > 32 {
+            6
+        } else {
+            5
+        }
+    }
+
+    /// Packs the alphabet indices of the letters into a `PackedWord`, the first letter in the
+    /// highest bits, so packed words sort like the words themselves.
+    ///
+    /// Returns `None` if a letter is not in the alphabet.
+    pub fn pack(&self, word: &Word) -> Option<PackedWord> {
+        let bits = self.letter_bits();
+        word.iter().try_fold(0, |packed, &letter| {
+            let index = self.index_of(letter)? as PackedWord;
+            Some(packed << bits | index)
+        })
+    }
+
+    /// Reverses `pack`, returning `None` if the value is not a word of the alphabet.
+    pub fn unpack(&self, packed: PackedWord) -> Option<Word> {
+        let bits = self.letter_bits();
+        if packed >> (bits * WORD_LENGTH as u32) != 0 {
+            return None;
+        }
+        let mut word = ['\0'; WORD_LENGTH];
+        for (i, letter) in word.iter_mut().enumerate() {
+            let shift = bits * (WORD_LENGTH - 1 - i) as u32;
+            let index = (packed >> shift) & ((1 << bits) - 1);
+            *letter = self.letters().chars().nth(index as usize)?;
+        }
+        Some(word)
+    }
+
+    /// The spelling `c` is replaced with before validation, if any.
+    fn fold(&self, c: char) -> Option<&'static str> {
+        let folds: &[(char, &str)] = match self {
+            Alphabet::English | Alphabet::German => &[],
+            Alphabet::Spanish => &[
+                ('á', "a"),
+                ('é', "e"),
+                ('í', "i"),
+                ('ó', "o"),
+                ('ú', "u"),
+                ('ü', "u"),
+            ],
+            Alphabet::GermanTransliterated => &[('ä', "ae"), ('ö', "oe"), ('ü', "ue"), ('ß', "ss")],
+            // The apostrophe is not a letter, e.g. `п'ять` is played as `пять`
+            Alphabet::Ukrainian => &[('\'', ""), ('ʼ', ""), ('’', "")],
+        };
+        folds.iter().find(|(from, _)| *from == c).map(|(_, to)| *to)
+    }
+
+    /// Checks that `word` spells exactly `WORD_LENGTH` letters of the alphabet.
+    ///
+    /// Letters are counted after folding, so `ñandú` is a Spanish word of five letters.
+    /// Uppercase letters are rejected rather than lowercased.
+    pub fn parse(&self, word: &str) -> Result<Word, WordError> {
+        let mut letters = ['\0'; WORD_LENGTH];
+        let mut len = 0;
+        for c in word.chars() {
+            let mut buf = [0; 4];
+            let spelling = match self.fold(c) {
+                Some(spelling) => spelling,
+                None => c.encode_utf8(&mut buf),
+            };
+            for letter in spelling.chars() {
+                if self.index_of(letter).is_none() {
+                    return Err(WordError::InvalidLetter);
+                }
+                *letters.get_mut(len).ok_or(WordError::InvalidLength)? = letter;
+                len += 1;
+            }
+        }
+        if len != WORD_LENGTH {
+            return Err(WordError::InvalidLength);
+        }
+        Ok(letters)
+    }
+}
+
+/// Checks that `word` is made of exactly `WORD_LENGTH` lowercase English letters.
+pub fn validate_word(word: &str) -> Result<Word, WordError> {
+    Alphabet::English.parse(word)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LetterState {
+    /// The letter is not in the secret (or all its occurrences are already matched).
+    Absent,
+    /// The letter is in the secret at another position.
+    Present,
+    /// The letter is at this position in the secret.
+    Correct,
+}
+
+/// Per-position result of a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Feedback(pub [LetterState; WORD_LENGTH]);
+
+impl Feedback {
+    /// Builds feedback from the index lists used by `WordChecked`.
+    pub fn from_positions(correct_positions: &[u8], contained_in_word: &[u8]) -> Self {
+        let mut states = [LetterState::Absent; WORD_LENGTH];
+        for &i in contained_in_word {
+            if let Some(state) = states.get_mut(i as usize) {
+                *state = LetterState::Present;
+            }
+        }
+        for &i in correct_positions {
+            if let Some(state) = states.get_mut(i as usize) {
+                *state = LetterState::Correct;
+            }
+        }
+        Self(states)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.0.iter().all(|state| *state == LetterState::Correct)
+    }
+
+    /// Indices of letters in the right position.
+    pub fn correct_positions(&self) -> impl Iterator<Item = u8> + '_ {
+        self.positions(LetterState::Correct)
+    }
+
+    /// Indices of letters present in the secret at another position.
+    pub fn contained_in_word(&self) -> impl Iterator<Item = u8> + '_ {
+        self.positions(LetterState::Present)
+    }
+
+    fn positions(&self, wanted: LetterState) -> impl Iterator<Item = u8> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(move |(_, state)| **state == wanted)
+            .map(|(i, _)| i as u8)
+    }
+}
+
+/// Scores `guess` against `secret`.
+///
+/// Exact matches are marked first; the remaining letters are marked present only
+/// while unmatched occurrences of that letter are left in the secret.
+pub fn evaluate(secret: &Word, guess: &Word) -> Feedback {
+    let mut states = [LetterState::Absent; WORD_LENGTH];
+    let mut unmatched = [None; WORD_LENGTH];
+
+    for i in 0..WORD_LENGTH {
+        if secret[i] == guess[i] {
+            states[i] = LetterState::Correct;
+        } else {
+            unmatched[i] = Some(secret[i]);
+        }
+    }
+    for i in 0..WORD_LENGTH {
+        if states[i] != LetterState::Correct && take(&mut unmatched, guess[i]) {
+            states[i] = LetterState::Present;
+        }
+    }
+
+    Feedback(states)
+}
+
+/// Removes one occurrence of `letter` from `letters`, returning whether there was one.
+fn take(letters: &mut [Option<char>; WORD_LENGTH], letter: char) -> bool {
+    match letters.iter_mut().find(|c| **c == Some(letter)) {
+        Some(c) => {
+            *c = None;
+            true
+        }
+        None => false,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HardModeError {
+    /// A letter found in the right position was moved or dropped.
+    MissingCorrect { position: u8, letter: char },
+    /// A letter known to be in the word was not reused.
+    MissingPresent { letter: char },
+}
+
+impl fmt::Display for HardModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HardModeError::MissingCorrect { position, letter } => {
+                write!(f, "Letter {} must be at position {}", letter, position + 1)
+            }
+            HardModeError::MissingPresent { letter } => {
+                write!(f, "The guess must contain {}", letter)
+            }
+        }
+    }
+}
+
+/// Checks that `guess` uses every hint revealed by the previous guess.
+pub fn check_hard_mode(
+    previous: &Word,
+    feedback: &Feedback,
+    guess: &Word,
+) -> Result<(), HardModeError> {
+    let mut available = [None; WORD_LENGTH];
+    for i in 0..WORD_LENGTH {
+        if feedback.0[i] == LetterState::Correct {
+            if guess[i] != previous[i] {
+                return Err(HardModeError::MissingCorrect {
+                    position: i as u8,
+                    letter: previous[i],
+                });
+            }
+        } else {
+            available[i] = Some(guess[i]);
+        }
+    }
+    for (&letter, state) in previous.iter().zip(feedback.0) {
+        if state == LetterState::Present && !take(&mut available, letter) {
+            return Err(HardModeError::MissingPresent { letter });
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameState {
+    InProgress,
+    Won,
+    Lost,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuessError {
+    GameOver,
+    HardMode(HardModeError),
+}
+
+/// A single game: the secret, the tries used and the last hint for hard mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Game {
+    secret: Word,
+    /// Without a limit, the game only ends once it is won.
+    tries_limit: Option<u8>,
+    hard_mode: bool,
+    tries: u8,
+    state: GameState,
+    last_guess: Option<(Word, Feedback)>,
+}
+
+impl Game {
+    pub fn new(secret: Word, tries_limit: Option<u8>, hard_mode: bool) -> Self {
+        Self {
+            secret,
+            tries_limit,
+            hard_mode,
+            tries: 0,
+            state: GameState::InProgress,
+            last_guess: None,
+        }
+    }
+
+    pub fn secret(&self) -> &Word {
+        &self.secret
+    }
+
+    /// Tries used, stopping at `u8::MAX` in games without a limit.
+    pub fn tries(&self) -> u8 {
+        self.tries
+    }
+
+    /// `None` in games without a limit.
+    pub fn tries_left(&self) -> Option<u8> {
+        self.tries_limit
+            .map(|tries_limit| tries_limit.saturating_sub(self.tries))
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Scores `guess`, counts the try and moves the game to `Won` or `Lost` when it ends.
+    pub fn guess(&mut self, guess: &Word) -> Result<Feedback, GuessError> {
+        if self.state != GameState::InProgress {
+            return Err(GuessError::GameOver);
+        }
+        if let (true, Some((previous, feedback))) = (self.hard_mode, &self.last_guess) {
+            check_hard_mode(previous, feedback, guess).map_err(GuessError::HardMode)?;
+        }
+
+        let feedback = evaluate(&self.secret, guess);
+        self.tries = self.tries.saturating_add(1);
+        self.last_guess = Some((*guess, feedback));
+        if feedback.is_solved() {
+            self.state = GameState::Won;
+        } else if self.tries_left() == Some(0) {
+            self.state = GameState::Lost;
+        }
+        Ok(feedback)
+    }
+}
@@ -0,0 +1,292 @@
+use proptest::prelude::*;
+use wordle_core::*;
+
+fn word(word: &str) -> Word {
+    validate_word(word).unwrap()
+}
+
+fn feedback(pattern: &str) -> Feedback {
+    let mut states = [LetterState::Absent; WORD_LENGTH];
+    for (state, c) in states.iter_mut().zip(pattern.chars()) {
+        *state = match c {
+            'g' => LetterState::Correct,
+            'y' => LetterState::Present,
+            _ => LetterState::Absent,
+        };
+    }
+    Feedback(states)
+}
+
+#[test]
+fn test_validate_word() {
+    assert_eq!(validate_word("horse"), Ok(['h', 'o', 'r', 's', 'e']));
+    assert_eq!(validate_word("hors"), Err(WordError::InvalidLength));
+    assert_eq!(validate_word("horses"), Err(WordError::InvalidLength));
+    assert_eq!(validate_word("Horse"), Err(WordError::InvalidLetter));
+    assert_eq!(validate_word("hors3"), Err(WordError::InvalidLetter));
+    // Five letters but more than five bytes
+    assert_eq!(validate_word("ñandú"), Err(WordError::InvalidLetter));
+    assert_eq!(validate_word("пісня"), Err(WordError::InvalidLetter));
+}
+
+#[test]
+fn test_alphabets() {
+    let spanish = Alphabet::Spanish;
+    assert_eq!(spanish.parse("ñandú"), Ok(['ñ', 'a', 'n', 'd', 'u']));
+    assert_eq!(spanish.parse("árbol"), spanish.parse("arbol"));
+    assert_eq!(spanish.parse("Ñandú"), Err(WordError::InvalidLetter));
+    assert_eq!(spanish.parse("ñandúes"), Err(WordError::InvalidLength));
+
+    // Umlauts and ß are either letters of their own or spelled out
+    let german = Alphabet::German;
+    assert_eq!(german.parse("größe"), Ok(['g', 'r', 'ö', 'ß', 'e']));
+    assert_eq!(german.parse("grösse"), Err(WordError::InvalidLength));
+    let transliterated = Alphabet::GermanTransliterated;
+    assert_eq!(transliterated.parse("größe"), Err(WordError::InvalidLength));
+    assert_eq!(transliterated.parse("büßen"), Err(WordError::InvalidLength));
+    assert_eq!(transliterated.parse("übel"), transliterated.parse("uebel"));
+    assert_eq!(transliterated.parse("maße"), Ok(['m', 'a', 's', 's', 'e']));
+
+    // Cyrillic letters are counted as letters, not bytes, and the apostrophe is dropped
+    let ukrainian = Alphabet::Ukrainian;
+    assert_eq!(ukrainian.parse("пісня"), Ok(['п', 'і', 'с', 'н', 'я']));
+    assert_eq!(ukrainian.parse("м'ятий"), Ok(['м', 'я', 'т', 'и', 'й']));
+    assert_eq!(ukrainian.parse("м'ятий"), ukrainian.parse("мʼятий"));
+    assert_eq!(ukrainian.parse("Пісня"), Err(WordError::InvalidLetter));
+    assert_eq!(ukrainian.parse("horse"), Err(WordError::InvalidLetter));
+
+    assert_eq!(Alphabet::from_name("de-translit"), Some(transliterated));
+    assert_eq!(Alphabet::from_name("uk"), Some(ukrainian));
+    assert_eq!(Alphabet::from_name("fr"), None);
+}
+
+#[test]
+fn test_pack() {
+    let english = Alphabet::English;
+    assert_eq!(english.letter_bits(), 5);
+    assert_eq!(english.pack(&word("aaaab")), Some(1));
+    assert_eq!(english.pack(&word("baaaa")), Some(1 << 20));
+    assert_eq!(english.pack(&['ñ', 'a', 'n', 'd', 'u']), None);
+    assert_eq!(english.unpack(1 << 25), None);
+    // Index 26 and above are not letters
+    assert_eq!(english.unpack(26), None);
+
+    // Packed words sort like the words
+    let mut words = ["horse", "house", "human", "abbey", "zebra"].map(word);
+    let mut packed = words.map(|word| english.pack(&word).unwrap());
+    words.sort();
+    packed.sort();
+    assert_eq!(packed.map(|packed| english.unpack(packed).unwrap()), words);
+
+    // Ukrainian has 33 letters, which takes six bits
+    let ukrainian = Alphabet::Ukrainian;
+    assert_eq!(ukrainian.letter_bits(), 6);
+    let word = ukrainian.parse("пісня").unwrap();
+    assert_eq!(ukrainian.unpack(ukrainian.pack(&word).unwrap()), Some(word));
+}
+
+#[test]
+fn test_evaluate() {
+    let cases = [
+        ("horse", "horse", "ggggg"),
+        ("horse", "abcde", "....g"),
+        ("horse", "house", "gg.gg"),
+        ("house", "human", "gy..."),
+        // Repeated guess letters are only present as often as in the secret
+        ("abbey", "bobby", "y.g.g"),
+        ("horse", "ooooo", ".g..."),
+        ("speed", "eerie", "yy..."),
+        ("eerie", "speed", "..yy."),
+        ("crane", "nacre", "yyyyg"),
+    ];
+    for (secret, guess, expected) in cases {
+        assert_eq!(
+            evaluate(&word(secret), &word(guess)),
+            feedback(expected),
+            "{} vs {}",
+            secret,
+            guess
+        );
+    }
+}
+
+#[test]
+fn test_feedback_positions() {
+    let feedback = feedback("gy.yg");
+    assert_eq!(feedback.correct_positions().collect::<Vec<_>>(), vec![0, 4]);
+    assert_eq!(feedback.contained_in_word().collect::<Vec<_>>(), vec![1, 3]);
+    assert_eq!(Feedback::from_positions(&[0, 4], &[1, 3]), feedback);
+    // Out-of-range indices are ignored
+    assert_eq!(Feedback::from_positions(&[0, 4, 9], &[1, 3, 200]), feedback);
+    assert!(!feedback.is_solved());
+    assert!(Feedback::from_positions(&[0, 1, 2, 3, 4], &[]).is_solved());
+}
+
+#[test]
+fn test_hard_mode() {
+    let previous = word("horse");
+    let hints = feedback("g.y..");
+    assert_eq!(check_hard_mode(&previous, &hints, &word("hairy")), Ok(()));
+    assert_eq!(
+        check_hard_mode(&previous, &hints, &word("array")),
+        Err(HardModeError::MissingCorrect {
+            position: 0,
+            letter: 'h'
+        })
+    );
+    assert_eq!(
+        check_hard_mode(&previous, &hints, &word("haunt")),
+        Err(HardModeError::MissingPresent { letter: 'r' })
+    );
+    // The letter at a correct position does not count towards a present hint
+    let previous = word("eerie");
+    let hints = feedback("gy...");
+    assert_eq!(
+        check_hard_mode(&previous, &hints, &word("exact")),
+        Err(HardModeError::MissingPresent { letter: 'e' })
+    );
+    assert_eq!(check_hard_mode(&previous, &hints, &word("elder")), Ok(()));
+}
+
+#[test]
+fn test_game_win() {
+    let mut game = Game::new(word("horse"), Some(5), false);
+    assert_eq!(game.guess(&word("abcde")), Ok(feedback("....g")));
+    assert_eq!(game.state(), GameState::InProgress);
+    assert_eq!(game.tries_left(), Some(4));
+    assert_eq!(game.guess(&word("horse")), Ok(feedback("ggggg")));
+    assert_eq!(game.state(), GameState::Won);
+    assert_eq!(game.tries(), 2);
+    assert_eq!(game.guess(&word("horse")), Err(GuessError::GameOver));
+}
+
+#[test]
+fn test_game_lose() {
+    let mut game = Game::new(word("horse"), Some(2), false);
+    game.guess(&word("house")).unwrap();
+    game.guess(&word("house")).unwrap();
+    assert_eq!(game.state(), GameState::Lost);
+    assert_eq!(game.tries_left(), Some(0));
+    assert_eq!(game.guess(&word("horse")), Err(GuessError::GameOver));
+}
+
+#[test]
+fn test_game_without_tries_limit() {
+    let mut game = Game::new(word("horse"), None, false);
+    for _ in 0..300 {
+        game.guess(&word("house")).unwrap();
+    }
+    assert_eq!(game.state(), GameState::InProgress);
+    assert_eq!(game.tries(), u8::MAX);
+    assert_eq!(game.tries_left(), None);
+    assert_eq!(game.guess(&word("horse")), Ok(feedback("ggggg")));
+    assert_eq!(game.state(), GameState::Won);
+}
+
+#[test]
+fn test_game_hard_mode() {
+    let mut game = Game::new(word("horse"), Some(5), true);
+    game.guess(&word("house")).unwrap();
+    assert_eq!(
+        game.guess(&word("abcde")),
+        Err(GuessError::HardMode(HardModeError::MissingCorrect {
+            position: 0,
+            letter: 'h'
+        }))
+    );
+    // A rejected guess does not use a try
+    assert_eq!(game.tries(), 1);
+    assert_eq!(game.guess(&word("horse")), Ok(feedback("ggggg")));
+}
+
+fn any_word() -> impl Strategy<Value = Word> {
+    // A small alphabet makes repeated letters common
+    proptest::array::uniform5(proptest::char::range('a', 'e'))
+}
+
+fn count(word: &Word, letter: char) -> usize {
+    word.iter().filter(|&&c| c == letter).count()
+}
+
+proptest! {
+    #[test]
+    fn prop_correct_iff_letters_match(secret in any_word(), guess in any_word()) {
+        let feedback = evaluate(&secret, &guess);
+        for i in 0..WORD_LENGTH {
+            prop_assert_eq!(feedback.0[i] == LetterState::Correct, secret[i] == guess[i]);
+        }
+    }
+
+    #[test]
+    fn prop_marked_letters_bounded_by_secret(secret in any_word(), guess in any_word()) {
+        let feedback = evaluate(&secret, &guess);
+        for letter in guess {
+            let marked = (0..WORD_LENGTH)
+                .filter(|&i| guess[i] == letter && feedback.0[i] != LetterState::Absent)
+                .count();
+            // Every occurrence is marked until the secret runs out of that letter
+            prop_assert_eq!(marked, count(&secret, letter).min(count(&guess, letter)));
+        }
+    }
+
+    #[test]
+    fn prop_solved_iff_equal(secret in any_word(), guess in any_word()) {
+        prop_assert_eq!(evaluate(&secret, &guess).is_solved(), secret == guess);
+    }
+
+    #[test]
+    fn prop_positions_unique_and_in_range(secret in any_word(), guess in any_word()) {
+        let feedback = evaluate(&secret, &guess);
+        for positions in [
+            feedback.correct_positions().collect::<Vec<_>>(),
+            feedback.contained_in_word().collect::<Vec<_>>(),
+        ] {
+            prop_assert!(positions.iter().all(|&i| (i as usize) < WORD_LENGTH));
+            prop_assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn prop_present_letters_bounded_by_secret(
+        secret in proptest::array::uniform5(proptest::char::range('a', 'z')),
+        guess in proptest::array::uniform5(proptest::char::range('a', 'z')),
+    ) {
+        let feedback = evaluate(&secret, &guess);
+        for letter in guess {
+            let present = feedback
+                .contained_in_word()
+                .filter(|&i| guess[i as usize] == letter)
+                .count();
+            let unmatched = (0..WORD_LENGTH)
+                .filter(|&i| secret[i] == letter && guess[i] != letter)
+                .count();
+            prop_assert!(present <= unmatched);
+        }
+    }
+
+    #[test]
+    fn prop_positions_round_trip(secret in any_word(), guess in any_word()) {
+        let feedback = evaluate(&secret, &guess);
+        let correct: Vec<u8> = feedback.correct_positions().collect();
+        let contained: Vec<u8> = feedback.contained_in_word().collect();
+        prop_assert!(correct.iter().all(|i| !contained.contains(i)));
+        prop_assert_eq!(Feedback::from_positions(&correct, &contained), feedback);
+    }
+
+    #[test]
+    fn prop_pack_round_trip(
+        first in proptest::array::uniform5(proptest::char::range('a', 'z')),
+        second in proptest::array::uniform5(proptest::char::range('a', 'z')),
+    ) {
+        let alphabet = Alphabet::English;
+        let (packed_first, packed_second) = (alphabet.pack(&first), alphabet.pack(&second));
+        prop_assert_eq!(packed_first.and_then(|packed| alphabet.unpack(packed)), Some(first));
+        prop_assert_eq!(packed_first.cmp(&packed_second), first.cmp(&second));
+    }
+
+    #[test]
+    fn prop_secret_always_passes_hard_mode(secret in any_word(), previous in any_word()) {
+        let feedback = evaluate(&secret, &previous);
+        prop_assert_eq!(check_hard_mode(&previous, &feedback, &secret), Ok(()));
+    }
+}
@@ -1,23 +1,168 @@
 #![no_std]
 
-use gmeta::{InOut, Metadata};
+use gmeta::{In, InOut, Metadata, Out};
 use gstd::{prelude::*, ActorId};
+use parity_scale_codec::DecodeAll;
+use wordle_core::{PackedWord, Word};
+
+mod merkle;
+pub use merkle::*;
 
 pub struct WordleMetadata;
 
 impl Metadata for WordleMetadata {
-    type Init = ();
+    type Init = In<WordleInit>;
     type Handle = InOut<Action, Event>;
     type Others = ();
     type Reply = ();
     type Signal = ();
-    type State = ();
+    type State = Out<WordleState>;
+}
+
+/// Id of the bank set up when `WordleInit` has no banks.
+pub const DEFAULT_BANK: &str = "en";
+
+/// Banks to pick secrets from; without any, the default English bank is set up as `DEFAULT_BANK`.
+#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
+pub struct WordleInit {
+    pub banks: Vec<BankInit>,
+}
+
+/// A named bank of words, e.g. `en`, `es`, `uk` or `themed:space`.
+#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
+pub struct BankInit {
+    pub id: String,
+    /// Alphabet the words and the guesses are spelled with.
+    pub alphabet: Alphabet,
+    /// Secrets to pick from.
+    pub words: Vec<String>,
+    /// Allowed guesses besides `words`; with none, any word of the alphabet is allowed.
+    pub guesses: Vec<String>,
+    /// Root of the Merkle tree over a dictionary of further allowed guesses, which then
+    /// need a proof.
+    pub guess_root: Option<MerkleHash>,
+}
+
+/// One of the two word lists of a bank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum WordList {
+    /// Secrets to pick from.
+    Answers,
+    /// Allowed guesses besides the answers.
+    Guesses,
+}
+
+/// Bytes a word takes in `Action::UploadWords`.
+pub const PACKED_WORD_BYTES: usize = 4;
+
+/// Packs `words` for `Action::UploadWords`: each `PackedWord` as four little-endian bytes.
+///
+/// Returns `None` if a word is not spelled with `alphabet`.
+pub fn pack_words(alphabet: wordle_core::Alphabet, words: &[Word]) -> Option<Vec<u8>> {
+    let mut packed = Vec::with_capacity(words.len() * PACKED_WORD_BYTES);
+    for word in words {
+        packed.extend(alphabet.pack(word)?.to_le_bytes());
+    }
+    Some(packed)
+}
+
+/// Splits the bytes of `Action::UploadWords` into packed words.
+///
+/// Returns `None` if the length is not a multiple of `PACKED_WORD_BYTES`.
+pub fn unpack_words(packed: &[u8]) -> Option<Vec<PackedWord>> {
+    if !packed.len().is_multiple_of(PACKED_WORD_BYTES) {
+        return None;
+    }
+    Some(
+        packed
+            .chunks_exact(PACKED_WORD_BYTES)
+            .map(|bytes| PackedWord::from_le_bytes(bytes.try_into().unwrap()))
+            .collect(),
+    )
+}
+
+/// Codec mirror of `wordle_core::Alphabet`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum Alphabet {
+    #[default]
+    English,
+    Spanish,
+    German,
+    GermanTransliterated,
+    Ukrainian,
+}
+
+impl From<Alphabet> for wordle_core::Alphabet {
+    fn from(alphabet: Alphabet) -> Self {
+        match alphabet {
+            Alphabet::English => Self::English,
+            Alphabet::Spanish => Self::Spanish,
+            Alphabet::German => Self::German,
+            Alphabet::GermanTransliterated => Self::GermanTransliterated,
+            Alphabet::Ukrainian => Self::Ukrainian,
+        }
+    }
+}
+
+impl From<wordle_core::Alphabet> for Alphabet {
+    fn from(alphabet: wordle_core::Alphabet) -> Self {
+        match alphabet {
+            wordle_core::Alphabet::English => Self::English,
+            wordle_core::Alphabet::Spanish => Self::Spanish,
+            wordle_core::Alphabet::German => Self::German,
+            wordle_core::Alphabet::GermanTransliterated => Self::GermanTransliterated,
+            wordle_core::Alphabet::Ukrainian => Self::Ukrainian,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
 pub enum Action {
-    StartGame { user: ActorId },
-    CheckWord { user: ActorId, word: String },
+    /// In hard mode, every guess must use the hints revealed by the previous one.
+    ///
+    /// Fails while a game of the user that another program started is in progress.
+    StartGame {
+        user: ActorId,
+        bank: String,
+        hard_mode: bool,
+    },
+    /// `proof` is only needed for guesses of the bank's Merkle-committed dictionary.
+    ///
+    /// Only the program that started the game can send it.
+    CheckWord {
+        user: ActorId,
+        word: String,
+        proof: Vec<MerkleHash>,
+    },
+    /// Owner only: add words packed by `pack_words` to a list of the bank `bank`.
+    ///
+    /// Large lists can be uploaded in several messages.
+    UploadWords {
+        bank: String,
+        list: WordList,
+        packed: Vec<u8>,
+    },
+    /// Owner only: replace the Merkle root of the bank's dictionary of allowed guesses.
+    SetGuessRoot {
+        bank: String,
+        root: Option<MerkleHash>,
+    },
+    /// End the user's game and reply with its secret word.
+    ///
+    /// Only the program that started the game can send it, which the session program does once
+    /// the game is over.
+    RevealWord { user: ActorId },
+    /// Reply with the letter of the user's secret word at a random position not in `known`.
+    ///
+    /// Only the program that started the game can send it.
+    RevealHint { user: ActorId, known: Vec<u8> },
+}
+
+impl Action {
+    /// Decodes a whole message payload; malformed payloads and trailing bytes are rejected.
+    pub fn from_payload(mut payload: &[u8]) -> Option<Self> {
+        Self::decode_all(&mut payload).ok()
+    }
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
@@ -30,4 +175,64 @@ pub enum Event {
         correct_positions: Vec<u8>,
         contained_in_word: Vec<u8>,
     },
+    /// The message payload is not a valid `Action`.
+    InvalidPayload,
+    /// The guess is not a word of the bank's alphabet or dictionary; the game is unchanged.
+    InvalidWord {
+        user: ActorId,
+    },
+    /// There is no bank with the requested id, or it has no words yet; no game was started.
+    UnknownBank {
+        user: ActorId,
+    },
+    WordsUploaded {
+        bank: String,
+        answers: u32,
+        guesses: u32,
+    },
+    GuessRootSet {
+        bank: String,
+        root: Option<MerkleHash>,
+    },
+    WordRevealed {
+        user: ActorId,
+        word: String,
+    },
+    HintRevealed {
+        user: ActorId,
+        position: u8,
+        letter: String,
+    },
+    /// The guess of a hard mode game drops a hint of the previous guess; no try is used.
+    HardModeViolated {
+        user: ActorId,
+        reason: String,
+    },
+}
+
+#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
+pub struct WordleState {
+    pub owner: ActorId,
+    pub banks: Vec<BankState>,
+    /// Answers each player already had, per bank, since their history was last reset.
+    pub played: Vec<PlayedWords>,
+}
+
+#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
+pub struct BankState {
+    pub id: String,
+    pub alphabet: Alphabet,
+    pub answers: u32,
+    pub guesses: u32,
+    pub guess_root: Option<MerkleHash>,
+}
+
+/// How many answers of `bank` the player `user` already had.
+///
+/// New secrets are picked among the others; once all were played, the history starts over.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct PlayedWords {
+    pub user: ActorId,
+    pub bank: String,
+    pub count: u32,
 }
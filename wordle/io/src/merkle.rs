@@ -0,0 +1,86 @@
+//! Merkle tree over a dictionary of packed words, so Wordle can check guesses against a
+//! dictionary it does not store.
+//!
+//! Leaves are the sorted, deduplicated packed words. Pairs are hashed in sorted order, so
+//! a proof is just the sibling hashes from the leaf up; a node left without a sibling is
+//! carried up to the next level as is.
+
+use gstd::prelude::*;
+use sha2::{Digest, Sha256};
+use wordle_core::PackedWord;
+
+/// Hash of a node of the tree, the root included.
+pub type MerkleHash = [u8; 32];
+
+// Distinct prefixes keep a leaf from passing for an inner node
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+fn leaf_hash(word: PackedWord) -> MerkleHash {
+    Sha256::new()
+        .chain_update([LEAF_PREFIX])
+        .chain_update(word.to_le_bytes())
+        .finalize()
+        .into()
+}
+
+fn node_hash(a: &MerkleHash, b: &MerkleHash) -> MerkleHash {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    Sha256::new()
+        .chain_update([NODE_PREFIX])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+/// The levels of the tree from the leaves up to the root, or `None` for no words.
+fn levels(words: &[PackedWord]) -> Option<Vec<Vec<MerkleHash>>> {
+    if words.is_empty() {
+        return None;
+    }
+    let mut words = words.to_vec();
+    words.sort_unstable();
+    words.dedup();
+    let mut levels = vec![words.into_iter().map(leaf_hash).collect::<Vec<_>>()];
+    while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+        let next = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => node_hash(left, right),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    Some(levels)
+}
+
+/// Root of the tree over `words`, or `None` for no words.
+pub fn merkle_root(words: &[PackedWord]) -> Option<MerkleHash> {
+    levels(words).map(|levels| levels[levels.len() - 1][0])
+}
+
+/// Proof that `word` is one of `words`, or `None` if it is not.
+pub fn merkle_proof(words: &[PackedWord], word: PackedWord) -> Option<Vec<MerkleHash>> {
+    let levels = levels(words)?;
+    let leaf = leaf_hash(word);
+    let mut index = levels[0].iter().position(|hash| *hash == leaf)?;
+    let mut proof = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        index /= 2;
+    }
+    Some(proof)
+}
+
+/// Checks that `proof` leads from `word` to `root`.
+pub fn verify_proof(root: &MerkleHash, word: PackedWord, proof: &[MerkleHash]) -> bool {
+    let hash = proof
+        .iter()
+        .fold(leaf_hash(word), |hash, sibling| node_hash(&hash, sibling));
+    hash == *root
+}
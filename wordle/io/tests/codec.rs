@@ -0,0 +1,85 @@
+use gstd::{prelude::Encode, ActorId};
+use proptest::prelude::*;
+use wordle_core::Alphabet;
+use wordle_io::*;
+
+fn any_proof() -> impl Strategy<Value = Vec<MerkleHash>> {
+    proptest::collection::vec(any::<MerkleHash>(), 0..4)
+}
+
+fn any_action() -> impl Strategy<Value = Action> {
+    let user = any::<[u8; 32]>().prop_map(ActorId::from);
+    prop_oneof![
+        (user.clone(), ".{0,12}", any::<bool>()).prop_map(|(user, bank, hard_mode)| {
+            Action::StartGame {
+                user,
+                bank,
+                hard_mode,
+            }
+        }),
+        (user.clone(), ".{0,8}", any_proof()).prop_map(|(user, word, proof)| Action::CheckWord {
+            user,
+            word,
+            proof
+        }),
+        (
+            ".{0,12}",
+            prop_oneof![Just(WordList::Answers), Just(WordList::Guesses)],
+            proptest::collection::vec(any::<u8>(), 0..64),
+        )
+            .prop_map(|(bank, list, packed)| Action::UploadWords { bank, list, packed }),
+        user.clone().prop_map(|user| Action::RevealWord { user }),
+        (user, proptest::collection::vec(any::<u8>(), 0..5))
+            .prop_map(|(user, known)| Action::RevealHint { user, known }),
+    ]
+}
+
+#[test]
+fn test_pack_words() {
+    let words = ["horse", "house"].map(|word| Alphabet::English.parse(word).unwrap());
+    let packed = pack_words(Alphabet::English, &words).unwrap();
+    assert_eq!(packed.len(), 2 * PACKED_WORD_BYTES);
+    let unpacked: Vec<_> = unpack_words(&packed)
+        .unwrap()
+        .into_iter()
+        .map(|word| Alphabet::English.unpack(word).unwrap())
+        .collect();
+    assert_eq!(unpacked, words);
+    assert_eq!(unpack_words(&packed[1..]), None);
+    assert_eq!(
+        pack_words(Alphabet::English, &[['ñ', 'a', 'n', 'd', 'u']]),
+        None
+    );
+}
+
+proptest! {
+    #[test]
+    fn prop_action_payload_round_trip(action in any_action()) {
+        let payload = action.encode();
+        let decoded = Action::from_payload(&payload);
+        prop_assert_eq!(decoded.map(|action| action.encode()), Some(payload));
+    }
+
+    #[test]
+    fn prop_malformed_payloads_are_rejected(
+        action in any_action(),
+        junk in proptest::collection::vec(any::<u8>(), 1..8),
+    ) {
+        let payload = action.encode();
+        for len in 0..payload.len() {
+            prop_assert!(Action::from_payload(&payload[..len]).is_none());
+        }
+        let mut payload = payload;
+        payload.extend(junk);
+        prop_assert!(Action::from_payload(&payload).is_none());
+    }
+
+    #[test]
+    fn prop_arbitrary_payloads_decode_canonically(
+        payload in proptest::collection::vec(prop_oneof![0u8..8, any::<u8>()], 0..64),
+    ) {
+        if let Some(action) = Action::from_payload(&payload) {
+            prop_assert_eq!(action.encode(), payload);
+        }
+    }
+}
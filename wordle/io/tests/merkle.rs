@@ -0,0 +1,72 @@
+use proptest::prelude::*;
+use wordle_core::{Alphabet, PackedWord};
+use wordle_io::*;
+
+fn packed(words: &[&str]) -> Vec<PackedWord> {
+    words
+        .iter()
+        .map(|word| {
+            let word = Alphabet::English.parse(word).unwrap();
+            Alphabet::English.pack(&word).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_merkle_proofs() {
+    let words = packed(&["horse", "house", "human", "abbey", "zebra"]);
+    let root = merkle_root(&words).unwrap();
+
+    // The order of the words does not matter
+    let mut reversed = words.clone();
+    reversed.reverse();
+    assert_eq!(merkle_root(&reversed), Some(root));
+
+    for &word in &words {
+        let proof = merkle_proof(&words, word).unwrap();
+        assert!(verify_proof(&root, word, &proof));
+        // A proof is only good for its own word and root
+        assert!(!verify_proof(&root, word + 1, &proof));
+        assert!(!verify_proof(&[0; 32], word, &proof));
+    }
+
+    let missing = packed(&["abcde"])[0];
+    assert_eq!(merkle_proof(&words, missing), None);
+    assert!(!verify_proof(&root, missing, &[]));
+
+    let mut proof = merkle_proof(&words, words[0]).unwrap();
+    proof[0][0] ^= 1;
+    assert!(!verify_proof(&root, words[0], &proof));
+}
+
+#[test]
+fn test_merkle_small_trees() {
+    assert_eq!(merkle_root(&[]), None);
+    assert_eq!(merkle_proof(&[], 1), None);
+
+    // A single word is its own root
+    let root = merkle_root(&[7]).unwrap();
+    assert_eq!(merkle_proof(&[7], 7), Some(vec![]));
+    assert!(verify_proof(&root, 7, &[]));
+    assert!(!verify_proof(&root, 8, &[]));
+}
+
+proptest! {
+    #[test]
+    fn prop_every_word_has_a_proof(
+        words in proptest::collection::vec(0..1u32 << 25, 1..100),
+        other in 0..1u32 << 25,
+    ) {
+        let root = merkle_root(&words).unwrap();
+        for &word in &words {
+            let proof = merkle_proof(&words, word).unwrap();
+            prop_assert!(verify_proof(&root, word, &proof));
+            if !words.contains(&other) {
+                prop_assert!(!verify_proof(&root, other, &proof));
+            }
+        }
+        if !words.contains(&other) {
+            prop_assert_eq!(merkle_proof(&words, other), None);
+        }
+    }
+}
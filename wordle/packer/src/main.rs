@@ -0,0 +1,177 @@
+//! Packs word files for `Action::UploadWords`, builds Merkle roots and proofs of guess
+//! dictionaries, and unpacks packed files for checking.
+
+use std::{env, fs, process};
+use wordle_core::{Alphabet, PackedWord, Word};
+use wordle_io::{merkle_proof, merkle_root, pack_words, unpack_words, MerkleHash};
+
+const USAGE: &str = "\
+Usage: wordle-packer <word-file> --out <file> [--alphabet <name>] [--chunk <n>]
+       wordle-packer <word-file> --root [--alphabet <name>]
+       wordle-packer <word-file> --proof <word> [--alphabet <name>]
+       wordle-packer --unpack <packed-file> [--alphabet <name>]
+
+Packs the whitespace-separated words of <word-file> (lines starting with `#`
+are skipped), sorted and without duplicates, into the bytes `UploadWords`
+takes. With `--chunk` every <n> words go to their own file, <file>.0,
+<file>.1 and so on, to upload them in several messages.
+
+`--root` prints the Merkle root of the words for `SetGuessRoot` and
+`--proof` the proof `CheckWord` needs for <word>, a hash per line, in hex.
+`--unpack` prints the words of a packed file.
+
+Alphabets: en (default), es, de, de-translit, uk.";
+
+enum Mode {
+    Pack { out: String, chunk: Option<usize> },
+    Root,
+    Proof(String),
+    Unpack,
+}
+
+struct Args {
+    input: String,
+    mode: Mode,
+    alphabet: Alphabet,
+}
+
+fn alphabet(name: &str) -> Result<Alphabet, String> {
+    Alphabet::from_name(name).ok_or_else(|| format!("Unknown alphabet `{}`", name))
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1);
+    let mut input = None;
+    let mut out = None;
+    let mut chunk = None;
+    let mut mode = None;
+    let mut alphabet_name = "en".to_string();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = Some(args.next().ok_or("--out needs a file")?),
+            "--alphabet" => alphabet_name = args.next().ok_or("--alphabet needs a name")?,
+            "--chunk" => {
+                chunk = Some(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|&n| n > 0)
+                        .ok_or("--chunk needs a positive number")?,
+                )
+            }
+            "--root" => mode = Some(Mode::Root),
+            "--proof" => mode = Some(Mode::Proof(args.next().ok_or("--proof needs a word")?)),
+            "--unpack" => mode = Some(Mode::Unpack),
+            "-h" | "--help" => return Err(String::new()),
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(format!("Unexpected argument `{}`", arg)),
+        }
+    }
+    let mode = match mode {
+        Some(mode) => mode,
+        None => Mode::Pack {
+            out: out.ok_or("Missing --out file")?,
+            chunk,
+        },
+    };
+    Ok(Args {
+        input: input.ok_or("Missing input file")?,
+        mode,
+        alphabet: alphabet(&alphabet_name)?,
+    })
+}
+
+fn read_words(text: &str, alphabet: Alphabet) -> Result<Vec<Word>, String> {
+    let mut words = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(str::split_whitespace)
+        .map(|word| {
+            alphabet
+                .parse(word)
+                .map_err(|e| format!("`{}`: {}", word, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // Packed words sort in alphabet order, which is not always the order of `char`s
+    words.sort_by_key(|word| alphabet.pack(word));
+    words.dedup();
+    Ok(words)
+}
+
+fn pack(alphabet: Alphabet, word: &Word) -> PackedWord {
+    alphabet
+        .pack(word)
+        .expect("Parsed words are in the alphabet")
+}
+
+fn hex(hash: &MerkleHash) -> String {
+    hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn unpack(args: &Args) -> Result<(), String> {
+    let bytes = fs::read(&args.input).map_err(|e| format!("{}: {}", args.input, e))?;
+    let packed = unpack_words(&bytes).ok_or("The file length is not a multiple of four")?;
+    for word in packed {
+        let word = args
+            .alphabet
+            .unpack(word)
+            .ok_or(format!("{:#010x} is not a word of the alphabet", word))?;
+        println!("{}", word.iter().collect::<String>());
+    }
+    Ok(())
+}
+
+fn run(args: Args) -> Result<(), String> {
+    if let Mode::Unpack = args.mode {
+        return unpack(&args);
+    }
+
+    let text = fs::read_to_string(&args.input).map_err(|e| format!("{}: {}", args.input, e))?;
+    let words = read_words(&text, args.alphabet)?;
+    let packed: Vec<PackedWord> = words.iter().map(|word| pack(args.alphabet, word)).collect();
+    match &args.mode {
+        Mode::Pack { out, chunk } => {
+            let write = |path: &str, words: &[Word]| {
+                let packed =
+                    pack_words(args.alphabet, words).expect("Parsed words are in the alphabet");
+                fs::write(path, &packed).map_err(|e| format!("{}: {}", path, e))?;
+                println!("{}: {} words, {} bytes", path, words.len(), packed.len());
+                Ok::<_, String>(())
+            };
+            match chunk {
+                Some(chunk) => {
+                    for (i, words) in words.chunks(*chunk).enumerate() {
+                        write(&format!("{}.{}", out, i), words)?;
+                    }
+                }
+                None => write(out, &words)?,
+            }
+        }
+        Mode::Root => {
+            let root = merkle_root(&packed).ok_or("The word file is empty")?;
+            println!("{}", hex(&root));
+        }
+        Mode::Proof(word) => {
+            let word = args
+                .alphabet
+                .parse(word)
+                .map_err(|e| format!("`{}`: {}", word, e))?;
+            let proof = merkle_proof(&packed, pack(args.alphabet, &word))
+                .ok_or("The word is not in the word file")?;
+            for hash in proof {
+                println!("{}", hex(&hash));
+            }
+        }
+        Mode::Unpack => unreachable!(),
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = parse_args().and_then(run) {
+        if !error.is_empty() {
+            eprintln!("{}\n", error);
+        }
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
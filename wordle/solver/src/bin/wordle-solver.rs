@@ -0,0 +1,94 @@
+use std::{env, fs, process};
+use wordle_core::{Alphabet, BANK_OF_WORDS};
+use wordle_solver::*;
+
+const USAGE: &str = "\
+Usage: wordle-solver <history-file> [--words <word-file>] [--alphabet <name>]
+                     [--top <n>]
+
+Each history line holds a guess and the `correct_positions` and
+`contained_in_word` indices from `CheckWordResult`, `-` for none:
+
+    house 0,1 -
+    horse 0,1,2,3,4 -
+
+Without `--words` the Wordle program's bank of words is used. Words are
+spelled in the `--alphabet` of the bank: en (default), es, de, de-translit
+or uk; the default bank of words is English.";
+
+struct Args {
+    history: String,
+    words: Option<String>,
+    alphabet: Alphabet,
+    top: usize,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1);
+    let mut history = None;
+    let mut words = None;
+    let mut alphabet = Alphabet::English;
+    let mut top = 5;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--words" => words = Some(args.next().ok_or("--words needs a file")?),
+            "--alphabet" => {
+                let name = args.next().ok_or("--alphabet needs a name")?;
+                alphabet = Alphabet::from_name(&name)
+                    .ok_or_else(|| format!("Unknown alphabet `{}`", name))?
+            }
+            "--top" => {
+                top = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("--top needs a number")?
+            }
+            "-h" | "--help" => return Err(String::new()),
+            _ if history.is_none() => history = Some(arg),
+            _ => return Err(format!("Unexpected argument `{}`", arg)),
+        }
+    }
+    if words.is_none() && alphabet != Alphabet::English {
+        return Err("--alphabet needs --words".to_string());
+    }
+    Ok(Args {
+        history: history.ok_or("Missing history file")?,
+        words,
+        alphabet,
+        top,
+    })
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let read = |path: &str| fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e));
+
+    let history = parse_history(&read(&args.history)?, args.alphabet).map_err(|e| e.to_string())?;
+    let words = match &args.words {
+        Some(path) => parse_words(&read(path)?, args.alphabet).map_err(|e| e.to_string())?,
+        None => BANK_OF_WORDS.to_vec(),
+    };
+
+    let remaining = candidates(&words, &history);
+    println!("{} candidate(s) left:", remaining.len());
+    for word in &remaining {
+        println!("  {}", word_to_string(word));
+    }
+    if remaining.len() > 1 {
+        println!("Suggested guesses:");
+        for (word, bits) in suggestions(&words, &remaining, args.top) {
+            println!("  {} ({:.2} bits)", word_to_string(&word), bits);
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let result = parse_args().and_then(run);
+    if let Err(error) = result {
+        if !error.is_empty() {
+            eprintln!("{}\n", error);
+        }
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
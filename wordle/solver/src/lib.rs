@@ -0,0 +1,165 @@
+//! Off-chain helper that narrows down the secret word from the guess history.
+
+use std::{fmt, str::FromStr};
+use wordle_core::{evaluate, Alphabet, Feedback, LetterState, Word, WordError, WORD_LENGTH};
+
+/// Number of distinct feedback patterns (three states per letter).
+const PATTERNS: usize = 3usize.pow(WORD_LENGTH as u32);
+
+/// A guess together with the feedback the game returned for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Turn {
+    pub guess: Word,
+    pub feedback: Feedback,
+}
+
+impl Turn {
+    /// Builds a turn from the fields of `GameSessionEvent::CheckWordResult`.
+    pub fn from_positions(guess: Word, correct_positions: &[u8], contained_in_word: &[u8]) -> Self {
+        Self {
+            guess,
+            feedback: Feedback::from_positions(correct_positions, contained_in_word),
+        }
+    }
+
+    /// Parses a history line: the guess, spelled in `alphabet`, then the comma-separated
+    /// `correct_positions` and `contained_in_word` indices, with `-` for an empty list,
+    /// e.g. `house 0,4 1`.
+    pub fn parse(line: &str, alphabet: Alphabet) -> Result<Self, ParseError> {
+        let mut fields = line.split_whitespace();
+        let mut next = || fields.next().ok_or(ParseError::MissingField);
+        let guess = alphabet.parse(next()?).map_err(ParseError::InvalidWord)?;
+        let correct_positions = parse_indices(next()?)?;
+        let contained_in_word = parse_indices(next()?)?;
+        Ok(Self::from_positions(
+            guess,
+            &correct_positions,
+            &contained_in_word,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    MissingField,
+    InvalidWord(WordError),
+    InvalidIndex(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingField => write!(f, "Expected `<guess> <correct> <contained>`"),
+            ParseError::InvalidWord(error) => write!(f, "{}", error),
+            ParseError::InvalidIndex(index) => write!(f, "Invalid letter index `{}`", index),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn parse_indices(field: &str) -> Result<Vec<u8>, ParseError> {
+    if field == "-" {
+        return Ok(Vec::new());
+    }
+    field
+        .split(',')
+        .map(|index| match index.parse::<u8>() {
+            Ok(i) if (i as usize) < WORD_LENGTH => Ok(i),
+            _ => Err(ParseError::InvalidIndex(index.to_string())),
+        })
+        .collect()
+}
+
+/// Parses a history line of English words, see [`Turn::parse`].
+impl FromStr for Turn {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Self::parse(line, Alphabet::English)
+    }
+}
+
+/// Parses a history file of words spelled in `alphabet`, skipping blank lines and `#` comments.
+pub fn parse_history(text: &str, alphabet: Alphabet) -> Result<Vec<Turn>, ParseError> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| Turn::parse(line, alphabet))
+        .collect()
+}
+
+/// Parses a word list with one word per line, spelled in `alphabet`.
+pub fn parse_words(text: &str, alphabet: Alphabet) -> Result<Vec<Word>, WordError> {
+    text.split_whitespace()
+        .map(|word| alphabet.parse(word))
+        .collect()
+}
+
+/// Index of the feedback pattern in `0..PATTERNS`.
+pub fn pattern_id(feedback: &Feedback) -> usize {
+    feedback.0.iter().fold(0, |id, state| {
+        id * 3
+            + match state {
+                LetterState::Absent => 0,
+                LetterState::Present => 1,
+                LetterState::Correct => 2,
+            }
+    })
+}
+
+/// Words that would have produced every feedback in `history`.
+pub fn candidates(words: &[Word], history: &[Turn]) -> Vec<Word> {
+    words
+        .iter()
+        .filter(|secret| {
+            history
+                .iter()
+                .all(|turn| evaluate(secret, &turn.guess) == turn.feedback)
+        })
+        .copied()
+        .collect()
+}
+
+/// Expected information (in bits) `guess` gives about a secret drawn from `candidates`.
+pub fn entropy(guess: &Word, candidates: &[Word]) -> f64 {
+    if candidates.is_empty() {
+        return 0.0;
+    }
+    let mut buckets = [0usize; PATTERNS];
+    for secret in candidates {
+        buckets[pattern_id(&evaluate(secret, guess))] += 1;
+    }
+    let total = candidates.len() as f64;
+    buckets
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The `limit` best guesses from `guesses`, highest entropy first.
+///
+/// Ties go to words that may still be the secret, then to alphabetical order.
+pub fn suggestions(guesses: &[Word], candidates: &[Word], limit: usize) -> Vec<(Word, f64)> {
+    let mut scored: Vec<(Word, f64)> = guesses
+        .iter()
+        .map(|guess| (*guess, entropy(guess, candidates)))
+        .collect();
+    scored.sort_by(|(a, a_entropy), (b, b_entropy)| {
+        b_entropy
+            .total_cmp(a_entropy)
+            .then_with(|| candidates.contains(b).cmp(&candidates.contains(a)))
+            .then_with(|| a.cmp(b))
+    });
+    scored.truncate(limit);
+    scored
+}
+
+/// Renders a word for display.
+pub fn word_to_string(word: &Word) -> String {
+    word.iter().collect()
+}
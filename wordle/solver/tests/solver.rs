@@ -0,0 +1,101 @@
+use wordle_core::{evaluate, validate_word, Alphabet, Word, BANK_OF_WORDS};
+use wordle_solver::*;
+
+fn word(word: &str) -> Word {
+    validate_word(word).unwrap()
+}
+
+#[test]
+fn test_parse_history() {
+    let history = parse_history(
+        "# first game\nhouse 0,1 -\n\nhuman 0 1\n",
+        Alphabet::English,
+    )
+    .unwrap();
+    assert_eq!(
+        history,
+        vec![
+            Turn::from_positions(word("house"), &[0, 1], &[]),
+            Turn::from_positions(word("human"), &[0], &[1]),
+        ]
+    );
+    assert_eq!(
+        parse_history("house 0,1", Alphabet::English),
+        Err(ParseError::MissingField)
+    );
+    assert_eq!(
+        parse_history("house 0,5 -", Alphabet::English),
+        Err(ParseError::InvalidIndex("5".to_string()))
+    );
+    assert!(matches!(
+        parse_history("House - -", Alphabet::English),
+        Err(ParseError::InvalidWord(_))
+    ));
+
+    // Guesses are spelled in the bank's alphabet, folded like the Wordle program does
+    assert_eq!(
+        parse_history("ñandú 0 1,2", Alphabet::Spanish),
+        Ok(vec![Turn::from_positions(
+            ['ñ', 'a', 'n', 'd', 'u'],
+            &[0],
+            &[1, 2]
+        )])
+    );
+    assert!(matches!(
+        parse_history("ñandú 0 -", Alphabet::English),
+        Err(ParseError::InvalidWord(_))
+    ));
+    assert_eq!(
+        parse_words("пісня\nм'ятий", Alphabet::Ukrainian),
+        Ok(vec![['п', 'і', 'с', 'н', 'я'], ['м', 'я', 'т', 'и', 'й']])
+    );
+}
+
+#[test]
+fn test_candidates() {
+    let secret = word("horse");
+    let history: Vec<Turn> = ["house", "human"]
+        .iter()
+        .map(|guess| Turn {
+            guess: word(guess),
+            feedback: evaluate(&secret, &word(guess)),
+        })
+        .collect();
+    assert_eq!(candidates(&BANK_OF_WORDS, &history[..1]), vec![secret]);
+    assert_eq!(candidates(&BANK_OF_WORDS, &history), vec![secret]);
+    assert_eq!(candidates(&BANK_OF_WORDS, &[]), BANK_OF_WORDS.to_vec());
+}
+
+#[test]
+fn test_entropy() {
+    let words = [word("house"), word("horse"), word("human")];
+    // "house" tells all three secrets apart, "hhhhh" tells none
+    assert!((entropy(&word("house"), &words) - 3f64.log2()).abs() < 1e-9);
+    assert_eq!(entropy(&word("hhhhh"), &words), 0.0);
+    assert_eq!(entropy(&word("house"), &[]), 0.0);
+}
+
+#[test]
+fn test_suggestions() {
+    let words = [word("hhhhh"), word("horse"), word("house"), word("human")];
+    let remaining = &words[1..];
+    let best = suggestions(&words, remaining, 2);
+    assert_eq!(best.len(), 2);
+    assert_eq!(best[0].0, word("horse"));
+    assert!(best.iter().all(|(guess, _)| *guess != word("hhhhh")));
+}
+
+#[test]
+fn test_pattern_id_is_unique() {
+    let mut seen = std::collections::HashSet::new();
+    for secret in ["aabbc", "abcde", "caaab", "eeeee"] {
+        for guess in ["aabbc", "abcde", "bacde", "ccccc", "eabcd"] {
+            let feedback = evaluate(&word(secret), &word(guess));
+            let id = pattern_id(&feedback);
+            assert!(id < 243);
+            seen.insert((id, feedback));
+        }
+    }
+    let ids: std::collections::HashSet<_> = seen.iter().map(|(id, _)| *id).collect();
+    assert_eq!(ids.len(), seen.len());
+}
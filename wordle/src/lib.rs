@@ -1,62 +1,364 @@
 #![no_std]
 use gstd::{collections::HashMap, exec, msg, prelude::*, ActorId};
+use wordle_core::{
+    Alphabet, Game, GameState, GuessError, PackedWord, Word, BANK_OF_WORDS, WORD_LENGTH,
+};
 use wordle_io::*;
 
 static mut WORDLE: Option<Wordle> = None;
 
-#[cfg(not(test))]
-const BANK_OF_WORDS: [&str; 3] = ["house", "human", "horse"];
-#[cfg(test)]
-const BANK_OF_WORDS: [&str; 1] = ["horse"];
+struct Wordle {
+    owner: ActorId,
+    banks: Vec<Bank>,
+    games: HashMap<ActorId, Secret>,
+    /// Answers each player already had, by player and bank index.
+    played: HashMap<(ActorId, usize), Played>,
+}
+
+impl Wordle {
+    fn bank_index(&self, id: &str) -> usize {
+        self.banks
+            .iter()
+            .position(|bank| bank.id == id)
+            .expect("There is no bank with this id")
+    }
+}
 
+/// Words packed by `Alphabet::pack`, sorted for binary search.
 #[derive(Default)]
-struct Wordle {
-    games: HashMap<ActorId, String>,
+struct WordSet(Vec<PackedWord>);
+
+impl WordSet {
+    fn extend(&mut self, words: impl IntoIterator<Item = PackedWord>) {
+        self.0.extend(words);
+        self.0.sort_unstable();
+        self.0.dedup();
+    }
+
+    fn contains(&self, word: PackedWord) -> bool {
+        self.0.binary_search(&word).is_ok()
+    }
+
+    fn len(&self) -> u32 {
+        self.0.len() as u32
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Indices of the answers of a bank a player already had, as a bitset.
+#[derive(Default)]
+struct Played {
+    bits: Vec<u64>,
+    count: u32,
+}
+
+impl Played {
+    fn insert(&mut self, index: u32) {
+        let (chunk, bit) = ((index / 64) as usize, index % 64);
+        if self.bits.len() <= chunk {
+            self.bits.resize(chunk + 1, 0);
+        }
+        if self.bits[chunk] & (1 << bit) == 0 {
+            self.bits[chunk] |= 1 << bit;
+            self.count += 1;
+        }
+    }
+
+    /// Index of the `n`-th answer not played yet, out of `len` answers.
+    fn nth_unplayed(&self, mut n: u32, len: u32) -> u32 {
+        for start in (0..len).step_by(64) {
+            let chunk = self.bits.get((start / 64) as usize).copied().unwrap_or(0);
+            let size = (len - start).min(64);
+            let unplayed = size - chunk.count_ones();
+            if n >= unplayed {
+                n -= unplayed;
+                continue;
+            }
+            for bit in 0..size {
+                if chunk & (1 << bit) == 0 {
+                    if n == 0 {
+                        return start + bit;
+                    }
+                    n -= 1;
+                }
+            }
+        }
+        unreachable!("There are fewer unplayed answers than `n`")
+    }
+}
+
+struct Bank {
+    id: String,
+    alphabet: Alphabet,
+    answers: WordSet,
+    /// Allowed guesses besides the answers.
+    guesses: WordSet,
+    /// Merkle root of a dictionary of further allowed guesses, checked against proofs.
+    ///
+    /// While it is unset and `guesses` is empty, any word of the alphabet is allowed.
+    guess_root: Option<MerkleHash>,
+}
+
+/// A player's game, holding the secret word, and the bank the word was picked from.
+struct Secret {
+    bank: usize,
+    /// The session program enforces the tries limit, so the game only ends here once it is won.
+    game: Game,
+    /// The program that started the game, the only one that can play it or reveal the word.
+    session: ActorId,
+}
+
+impl Bank {
+    fn new(bank_init: BankInit) -> Self {
+        let alphabet: Alphabet = bank_init.alphabet.into();
+        let pack = |words: Vec<String>| {
+            words.into_iter().map(move |word| {
+                let word = alphabet.parse(&word).expect("Invalid word in the bank");
+                alphabet
+                    .pack(&word)
+                    .expect("Parsed words are in the alphabet")
+            })
+        };
+        let mut bank = Self {
+            id: bank_init.id,
+            alphabet,
+            answers: WordSet::default(),
+            guesses: WordSet::default(),
+            guess_root: bank_init.guess_root,
+        };
+        bank.answers.extend(pack(bank_init.words));
+        bank.guesses.extend(pack(bank_init.guesses));
+        bank
+    }
+
+    fn upload(&mut self, list: WordList, packed: &[u8]) {
+        let words = unpack_words(packed).expect("Packed words take four bytes each");
+        assert!(
+            words
+                .iter()
+                .all(|&word| self.alphabet.unpack(word).is_some()),
+            "Invalid packed word"
+        );
+        match list {
+            WordList::Answers => self.answers.extend(words),
+            WordList::Guesses => self.guesses.extend(words),
+        }
+    }
+
+    /// Picks an answer the player has not had yet, starting over once they had them all.
+    fn pick_secret(&self, played: &mut Played) -> Word {
+        let len = self.answers.len();
+        if played.count >= len {
+            *played = Played::default();
+        }
+        let index = played.nth_unplayed(get_random_value(len - played.count), len);
+        played.insert(index);
+        self.alphabet
+            .unpack(self.answers.0[index as usize])
+            .expect("Stored words are in the alphabet")
+    }
+
+    fn is_allowed(&self, word: &Word, proof: &[MerkleHash]) -> bool {
+        let packed = self
+            .alphabet
+            .pack(word)
+            .expect("Parsed words are in the alphabet");
+        match &self.guess_root {
+            None if self.guesses.is_empty() => true,
+            root => {
+                self.answers.contains(packed)
+                    || self.guesses.contains(packed)
+                    || root.is_some_and(|root| verify_proof(&root, packed, proof))
+            }
+        }
+    }
 }
 
 #[no_mangle]
 extern "C" fn init() {
+    let wordle_init: WordleInit = msg::load().expect("Unable to decode `WordleInit`");
+    let banks: Vec<Bank> = if wordle_init.banks.is_empty() {
+        let alphabet = Alphabet::English;
+        let mut bank = Bank {
+            id: DEFAULT_BANK.to_string(),
+            alphabet,
+            answers: WordSet::default(),
+            guesses: WordSet::default(),
+            guess_root: None,
+        };
+        bank.answers
+            .extend(BANK_OF_WORDS.iter().filter_map(|word| alphabet.pack(word)));
+        vec![bank]
+    } else {
+        wordle_init.banks.into_iter().map(Bank::new).collect()
+    };
+    for (i, bank) in banks.iter().enumerate() {
+        assert!(
+            banks[..i].iter().all(|other| other.id != bank.id),
+            "Duplicate bank id"
+        );
+    }
     unsafe {
         WORDLE = Some(Wordle {
+            owner: msg::source(),
+            banks,
             games: HashMap::new(),
+            played: HashMap::new(),
         });
     }
 }
 
 #[no_mangle]
 extern "C" fn handle() {
-    let action: Action = msg::load().expect("Unable to decode ");
+    let payload = msg::load_bytes().expect("Unable to load the payload");
+    let Some(action) = Action::from_payload(&payload) else {
+        msg::reply(Event::InvalidPayload, 0).expect("Error in sending a reply");
+        return;
+    };
     let wordle = unsafe { WORDLE.as_mut().expect("The program is not initialized") };
 
     let reply = match action {
-        Action::StartGame { user } => {
-            let random_id = get_random_value(BANK_OF_WORDS.len() as u8);
-            let word = BANK_OF_WORDS[random_id as usize];
-            wordle.games.insert(user, word.to_string());
+        Action::StartGame {
+            user,
+            bank,
+            hard_mode,
+        } => {
+            let Some(bank) = wordle
+                .banks
+                .iter()
+                .position(|other| other.id == bank && !other.answers.is_empty())
+            else {
+                msg::reply(Event::UnknownBank { user }, 0).expect("Error in sending a reply");
+                return;
+            };
+            // Another program cannot take over a game in progress
+            assert!(
+                !wordle.games.get(&user).is_some_and(|secret| {
+                    secret.session != msg::source() && secret.game.state() == GameState::InProgress
+                }),
+                "The game was started by another program"
+            );
+            let played = wordle.played.entry((user, bank)).or_default();
+            let word = wordle.banks[bank].pick_secret(played);
+            wordle.games.insert(
+                user,
+                Secret {
+                    bank,
+                    game: Game::new(word, None, hard_mode),
+                    session: msg::source(),
+                },
+            );
             Event::GameStarted { user }
         }
-        Action::CheckWord { user, word } => {
-            if word.len() != 5 {
-                panic!("The length of the word exceeds 5");
-            }
-            let key_word = wordle
+        Action::CheckWord { user, word, proof } => {
+            let secret = wordle
                 .games
-                .get(&user)
+                .get_mut(&user)
                 .expect("There is no game with this user");
-            let mut matched_indices = Vec::with_capacity(5);
-            let mut key_indices = Vec::with_capacity(5);
-            for (i, (a, b)) in key_word.chars().zip(word.chars()).enumerate() {
-                if a == b {
-                    matched_indices.push(i as u8);
-                } else if key_word.contains(b) {
-                    key_indices.push(i as u8);
+            assert_eq!(
+                msg::source(),
+                secret.session,
+                "Only the session program can check words"
+            );
+            let bank = &wordle.banks[secret.bank];
+            let Some(word) = bank
+                .alphabet
+                .parse(&word)
+                .ok()
+                .filter(|word| bank.is_allowed(word, &proof))
+            else {
+                msg::reply(Event::InvalidWord { user }, 0).expect("Error in sending a reply");
+                return;
+            };
+            let feedback = match secret.game.guess(&word) {
+                Ok(feedback) => feedback,
+                Err(GuessError::HardMode(error)) => {
+                    msg::reply(
+                        Event::HardModeViolated {
+                            user,
+                            reason: error.to_string(),
+                        },
+                        0,
+                    )
+                    .expect("Error in sending a reply");
+                    return;
                 }
-            }
+                Err(GuessError::GameOver) => panic!("The game is over"),
+            };
 
             Event::WordChecked {
                 user,
-                correct_positions: matched_indices,
-                contained_in_word: key_indices,
+                correct_positions: feedback.correct_positions().collect(),
+                contained_in_word: feedback.contained_in_word().collect(),
+            }
+        }
+        Action::UploadWords { bank, list, packed } => {
+            assert_eq!(
+                msg::source(),
+                wordle.owner,
+                "Only the owner can upload words"
+            );
+            let index = wordle.bank_index(&bank);
+            let stored = &mut wordle.banks[index];
+            stored.upload(list, &packed);
+            if list == WordList::Answers {
+                // New answers shift the indices the histories refer to
+                wordle.played.retain(|(_, bank), _| *bank != index);
+            }
+            Event::WordsUploaded {
+                bank,
+                answers: stored.answers.len(),
+                guesses: stored.guesses.len(),
+            }
+        }
+        Action::SetGuessRoot { bank, root } => {
+            assert_eq!(
+                msg::source(),
+                wordle.owner,
+                "Only the owner can set the guess root"
+            );
+            let index = wordle.bank_index(&bank);
+            wordle.banks[index].guess_root = root;
+            Event::GuessRootSet { bank, root }
+        }
+        Action::RevealWord { user } => {
+            let secret = wordle
+                .games
+                .get(&user)
+                .expect("There is no game with this user");
+            assert_eq!(
+                msg::source(),
+                secret.session,
+                "Only the session program can reveal the word"
+            );
+            let secret = wordle.games.remove(&user).expect("The game exists");
+            Event::WordRevealed {
+                user,
+                word: secret.game.secret().iter().collect(),
+            }
+        }
+        Action::RevealHint { user, known } => {
+            let secret = wordle
+                .games
+                .get(&user)
+                .expect("There is no game with this user");
+            assert_eq!(
+                msg::source(),
+                secret.session,
+                "Only the session program can reveal hints"
+            );
+            let unknown: Vec<usize> = (0..WORD_LENGTH)
+                .filter(|position| !known.contains(&(*position as u8)))
+                .collect();
+            assert!(!unknown.is_empty(), "Every position is known");
+            let position = unknown[get_random_value(unknown.len() as u32) as usize];
+            Event::HintRevealed {
+                user,
+                position: position as u8,
+                letter: secret.game.secret()[position].to_string(),
             }
         }
     };
@@ -64,13 +366,47 @@ extern "C" fn handle() {
     msg::reply(reply, 0).expect("Error in sending a reply");
 }
 
+#[no_mangle]
+extern "C" fn state() {
+    let wordle = unsafe { WORDLE.as_ref().expect("The program is not initialized") };
+    msg::reply::<WordleState>(wordle.into(), 0).expect("Failed to encode or reply from `state()`");
+}
+
+impl From<&Wordle> for WordleState {
+    fn from(wordle: &Wordle) -> Self {
+        Self {
+            owner: wordle.owner,
+            banks: wordle
+                .banks
+                .iter()
+                .map(|bank| BankState {
+                    id: bank.id.clone(),
+                    alphabet: bank.alphabet.into(),
+                    answers: bank.answers.len(),
+                    guesses: bank.guesses.len(),
+                    guess_root: bank.guess_root,
+                })
+                .collect(),
+            played: wordle
+                .played
+                .iter()
+                .map(|((user, bank), played)| PlayedWords {
+                    user: *user,
+                    bank: wordle.banks[*bank].id.clone(),
+                    count: played.count,
+                })
+                .collect(),
+        }
+    }
+}
+
 static mut SEED: u8 = 0;
 
-pub fn get_random_value(range: u8) -> u8 {
+pub fn get_random_value(range: u32) -> u32 {
     let seed = unsafe { SEED };
     unsafe { SEED = SEED.wrapping_add(1) };
     let mut random_input: [u8; 32] = exec::program_id().into();
     random_input[0] = random_input[0].wrapping_add(seed);
     let (random, _) = exec::random(random_input).expect("Error in getting random number");
-    random[0] % range
+    u32::from_le_bytes([random[0], random[1], random[2], random[3]]) % range
 }